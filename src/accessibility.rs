@@ -0,0 +1,244 @@
+//! Accessibility processing of keyboard input.
+//!
+//! Implements sticky keys, slow keys and bounce keys on top of the
+//! input events from the back-end, so applications can offer these
+//! features consistently across platforms.
+
+use std::collections::{ HashMap, HashSet };
+
+use { Button, Input, Key };
+
+/// Processes keyboard input with accessibility features.
+///
+/// All features are disabled by default.
+/// Inputs that are not keyboard presses or releases are passed through.
+#[derive(Clone, Debug)]
+pub struct Accessibility {
+    /// Latch modifier keys until the next key is released.
+    pub sticky_keys: bool,
+    /// Seconds a key must be held before the press registers.
+    pub slow_keys: Option<f64>,
+    /// Seconds after a release in which presses of the same key are ignored.
+    pub bounce_keys: Option<f64>,
+    time: f64,
+    last_release: HashMap<Key, f64>,
+    bounced: HashSet<Key>,
+    pending: HashMap<Key, f64>,
+    held_modifiers: HashSet<Key>,
+    latched: HashSet<Key>,
+    // Latched modifiers pressed again, released on their next release.
+    unlatching: HashSet<Key>,
+    chorded: bool,
+}
+
+impl Accessibility {
+    /// Creates a new accessibility stage with all features disabled.
+    pub fn new() -> Accessibility {
+        Accessibility {
+            sticky_keys: false,
+            slow_keys: None,
+            bounce_keys: None,
+            time: 0.0,
+            last_release: HashMap::new(),
+            bounced: HashSet::new(),
+            pending: HashMap::new(),
+            held_modifiers: HashSet::new(),
+            latched: HashSet::new(),
+            unlatching: HashSet::new(),
+            chorded: false,
+        }
+    }
+
+    /// Processes an input, returning the inputs to pass on.
+    pub fn handle_input(&mut self, input: &Input) -> Vec<Input> {
+        let mut out = vec![];
+        match *input {
+            Input::Press(Button::Keyboard(key)) => {
+                if let Some(bounce) = self.bounce_keys {
+                    let bouncing = match self.last_release.get(&key) {
+                        Some(&t) => self.time - t < bounce,
+                        None => false,
+                    };
+                    if bouncing || self.bounced.contains(&key) {
+                        self.bounced.insert(key);
+                        return out;
+                    }
+                }
+                if self.slow_keys.is_some() {
+                    self.pending.entry(key).or_insert(0.0);
+                    return out;
+                }
+                self.sticky(Input::Press(Button::Keyboard(key)), &mut out);
+            }
+            Input::Release(Button::Keyboard(key)) => {
+                if self.bounced.remove(&key) { return out; }
+                self.last_release.insert(key, self.time);
+                if self.pending.remove(&key).is_some() { return out; }
+                self.sticky(Input::Release(Button::Keyboard(key)), &mut out);
+            }
+            Input::Focus(false) => {
+                // Latched modifiers were pressed downstream,
+                // but their releases will never arrive.
+                let mut latched: Vec<Key> = self.latched.drain()
+                    .chain(self.unlatching.drain()).collect();
+                latched.sort();
+                for key in latched {
+                    out.push(Input::Release(Button::Keyboard(key)));
+                }
+                self.pending.clear();
+                self.held_modifiers.clear();
+                self.chorded = false;
+                out.push(input.clone());
+            }
+            ref x => out.push(x.clone()),
+        }
+        out
+    }
+
+    /// Advances time, returning presses accepted by slow keys.
+    pub fn update(&mut self, dt: f64) -> Vec<Input> {
+        self.time += dt;
+        let mut out = vec![];
+        let threshold = match self.slow_keys {
+            Some(threshold) => threshold,
+            None => return out,
+        };
+        let mut accepted = vec![];
+        for (&key, held) in self.pending.iter_mut() {
+            *held += dt;
+            if *held >= threshold { accepted.push(key); }
+        }
+        accepted.sort();
+        for key in accepted {
+            self.pending.remove(&key);
+            self.sticky(Input::Press(Button::Keyboard(key)), &mut out);
+        }
+        out
+    }
+
+    fn sticky(&mut self, input: Input, out: &mut Vec<Input>) {
+        if !self.sticky_keys {
+            out.push(input);
+            return;
+        }
+        match input {
+            Input::Press(Button::Keyboard(key)) if is_modifier(key) => {
+                self.held_modifiers.insert(key);
+                self.chorded = false;
+                // Pressing a latched modifier again unlatches it on release.
+                if self.latched.remove(&key) {
+                    self.unlatching.insert(key);
+                } else {
+                    out.push(input);
+                }
+            }
+            Input::Release(Button::Keyboard(key)) if is_modifier(key) => {
+                self.held_modifiers.remove(&key);
+                if self.unlatching.remove(&key) || self.chorded ||
+                   self.latched.contains(&key) {
+                    out.push(input);
+                } else {
+                    self.latched.insert(key);
+                }
+            }
+            Input::Press(_) => {
+                if !self.held_modifiers.is_empty() { self.chorded = true; }
+                out.push(input);
+            }
+            Input::Release(_) => {
+                out.push(input);
+                let mut latched: Vec<Key> = self.latched.drain().collect();
+                latched.sort();
+                for key in latched {
+                    out.push(Input::Release(Button::Keyboard(key)));
+                }
+            }
+            x => out.push(x),
+        }
+    }
+}
+
+impl Default for Accessibility {
+    fn default() -> Accessibility { Accessibility::new() }
+}
+
+fn is_modifier(key: Key) -> bool {
+    match key {
+        Key::LCtrl | Key::RCtrl | Key::LShift | Key::RShift
+      | Key::LAlt | Key::RAlt | Key::LGui | Key::RGui => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use { Button, Input, Key };
+
+    fn press(key: Key) -> Input { Input::Press(Button::Keyboard(key)) }
+    fn release(key: Key) -> Input { Input::Release(Button::Keyboard(key)) }
+
+    #[test]
+    fn test_sticky_keys() {
+        let mut a = Accessibility::new();
+        a.sticky_keys = true;
+        assert_eq!(a.handle_input(&press(Key::LShift)), vec![press(Key::LShift)]);
+        assert!(a.handle_input(&release(Key::LShift)).is_empty());
+        assert_eq!(a.handle_input(&press(Key::A)), vec![press(Key::A)]);
+        assert_eq!(a.handle_input(&release(Key::A)),
+                   vec![release(Key::A), release(Key::LShift)]);
+    }
+
+    #[test]
+    fn test_sticky_keys_unlatch() {
+        let mut a = Accessibility::new();
+        a.sticky_keys = true;
+        assert_eq!(a.handle_input(&press(Key::LCtrl)), vec![press(Key::LCtrl)]);
+        assert!(a.handle_input(&release(Key::LCtrl)).is_empty());
+        assert!(a.handle_input(&press(Key::LCtrl)).is_empty());
+        assert_eq!(a.handle_input(&release(Key::LCtrl)), vec![release(Key::LCtrl)]);
+        // No longer latched.
+        assert_eq!(a.handle_input(&press(Key::A)), vec![press(Key::A)]);
+        assert_eq!(a.handle_input(&release(Key::A)), vec![release(Key::A)]);
+    }
+
+    #[test]
+    fn test_focus_loss() {
+        let mut a = Accessibility::new();
+        a.sticky_keys = true;
+        a.slow_keys = Some(0.5);
+        a.handle_input(&press(Key::LShift));
+        a.update(1.0);
+        a.handle_input(&release(Key::LShift));
+        a.handle_input(&press(Key::B));
+        assert_eq!(a.handle_input(&Input::Focus(false)),
+                   vec![release(Key::LShift), Input::Focus(false)]);
+        a.slow_keys = None;
+        assert_eq!(a.handle_input(&press(Key::LShift)), vec![press(Key::LShift)]);
+        assert!(a.handle_input(&release(Key::LShift)).is_empty());
+        assert_eq!(a.handle_input(&press(Key::LCtrl)), vec![press(Key::LCtrl)]);
+        assert!(a.handle_input(&release(Key::LCtrl)).is_empty());
+        assert!(a.handle_input(&press(Key::LShift)).is_empty());
+        assert_eq!(a.handle_input(&Input::Focus(false)),
+                   vec![release(Key::LCtrl), release(Key::LShift), Input::Focus(false)]);
+        a.slow_keys = Some(0.5);
+        assert!(a.update(1.0).is_empty());
+        a.slow_keys = None;
+        assert_eq!(a.handle_input(&press(Key::A)), vec![press(Key::A)]);
+        assert_eq!(a.handle_input(&release(Key::A)), vec![release(Key::A)]);
+    }
+
+    #[test]
+    fn test_slow_and_bounce_keys() {
+        let mut a = Accessibility::new();
+        a.slow_keys = Some(0.5);
+        a.bounce_keys = Some(0.2);
+        assert!(a.handle_input(&press(Key::A)).is_empty());
+        assert!(a.update(0.3).is_empty());
+        assert_eq!(a.update(0.3), vec![press(Key::A)]);
+        assert_eq!(a.handle_input(&release(Key::A)), vec![release(Key::A)]);
+        a.update(0.1);
+        assert!(a.handle_input(&press(Key::A)).is_empty());
+        assert!(a.handle_input(&release(Key::A)).is_empty());
+    }
+}
@@ -0,0 +1,747 @@
+//! Mapping of buttons to application defined actions.
+
+use std::cell::RefCell;
+use std::collections::{ HashMap, HashSet };
+use std::fmt;
+use std::hash::Hash;
+use std::rc::Rc;
+
+use { Button, Input };
+use active::DeviceKind;
+
+/// A change of an action.
+#[derive(Copy, Clone, RustcDecodable, RustcEncodable, PartialEq, Eq, Hash, Debug)]
+pub enum ActionEvent<A> {
+    /// The action started.
+    Press(A),
+    /// The action stopped.
+    Release(A),
+    /// The action was tapped, starting and stopping at once.
+    Tap(A),
+}
+
+impl<A: Copy> ActionEvent<A> {
+    /// Returns the action of the event.
+    pub fn action(&self) -> A {
+        match *self {
+            ActionEvent::Press(a) | ActionEvent::Release(a) | ActionEvent::Tap(a) => a,
+        }
+    }
+}
+
+/// When a binding triggers its action.
+#[derive(Copy, Clone, Debug)]
+enum Trigger<A> {
+    // On press.
+    Press,
+    // Taps the action when released within the seconds,
+    // or presses the hold action when held longer.
+    TapHold(A, f64),
+    // Taps the action on release, if released within the seconds.
+    Release(Option<f64>),
+}
+
+/// A button bound to an action.
+#[derive(Copy, Clone, Debug)]
+struct Binding<A> {
+    action: A,
+    trigger: Trigger<A>,
+    // Whether presses are hidden from lower priority contexts.
+    consume: bool,
+}
+
+impl<A> Binding<A> {
+    fn new(action: A, consume: bool) -> Binding<A> {
+        Binding {
+            action: action,
+            trigger: Trigger::Press,
+            consume: consume,
+        }
+    }
+}
+
+type Callback<A> = Rc<RefCell<dyn FnMut(&ActionEvent<A>)>>;
+
+/// Callbacks of actions, shared between clones of an action map.
+#[derive(Clone)]
+struct Callbacks<A>(Vec<(A, Callback<A>)>);
+
+impl<A> fmt::Debug for Callbacks<A> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} callbacks", self.0.len())
+    }
+}
+
+/// Bindings active while a shift button is held.
+#[derive(Clone, Debug)]
+struct Layer<A> {
+    shift: Button,
+    bindings: HashMap<Button, Binding<A>>,
+}
+
+/// Maps buttons to actions.
+///
+/// Several buttons can be bound to the same action, e.g. a primary and
+/// a secondary key and a gamepad button. The action is pressed by the
+/// first of them and released when the last of them is released.
+///
+/// Layers of bindings are active while their shift button is held,
+/// like the Fn key of a keyboard. Bindings in an active layer shadow
+/// the base bindings, and layers added later shadow earlier ones.
+///
+/// Bindings of gamepad buttons are pad-independent. Set `gamepad`
+/// to map a single gamepad, e.g. for each player in local multiplayer.
+#[derive(Clone, Debug)]
+pub struct ActionMap<A> {
+    /// The gamepad whose buttons are mapped, or `None` for any gamepad.
+    pub gamepad: Option<u32>,
+    bindings: HashMap<Button, Binding<A>>,
+    // Buttons of the base layer in the order they were bound.
+    order: Vec<Button>,
+    layers: Vec<Layer<A>>,
+    held: HashSet<Button>,
+    // Actions started by held buttons.
+    pressed: HashMap<Button, A>,
+    // Held buttons waiting to trigger, and seconds held.
+    pending: HashMap<Button, (Binding<A>, f64)>,
+    callbacks: Callbacks<A>,
+}
+
+impl<A: Copy + Eq + Hash> ActionMap<A> {
+    /// Creates a new action map without bindings.
+    pub fn new() -> ActionMap<A> {
+        ActionMap {
+            gamepad: None,
+            bindings: HashMap::new(),
+            order: vec![],
+            layers: vec![],
+            held: HashSet::new(),
+            pressed: HashMap::new(),
+            pending: HashMap::new(),
+            callbacks: Callbacks(vec![]),
+        }
+    }
+
+    /// Binds a button to an action in the base layer.
+    pub fn bind(&mut self, button: Button, action: A) {
+        self.insert(button, Binding::new(action, true));
+    }
+
+    /// Binds a button to an action in the base layer without consuming
+    /// presses, which are also passed to lower priority contexts.
+    pub fn bind_pass_through(&mut self, button: Button, action: A) {
+        self.insert(button, Binding::new(action, false));
+    }
+
+    /// Binds a button to a tap action when released within `threshold`
+    /// seconds, or to a hold action when held longer.
+    ///
+    /// The tap is reported on release, and the hold when the threshold
+    /// passes in `update`.
+    pub fn bind_tap_hold(&mut self, button: Button, tap: A, hold: A, threshold: f64) {
+        self.insert(button, Binding {
+            action: tap,
+            trigger: Trigger::TapHold(hold, threshold),
+            consume: true,
+        });
+    }
+
+    /// Binds a button to an action while `shift` is held.
+    pub fn bind_layer(&mut self, shift: Button, button: Button, action: A) {
+        let (shift, button) = (shift.any_gamepad(), button.any_gamepad());
+        let binding = Binding::new(action, true);
+        if let Some(layer) = self.layers.iter_mut().find(|layer| layer.shift == shift) {
+            layer.bindings.insert(button, binding);
+            return;
+        }
+        let mut bindings = HashMap::new();
+        bindings.insert(button, binding);
+        self.layers.push(Layer { shift: shift, bindings: bindings });
+    }
+
+    /// Removes a binding from the base layer.
+    pub fn unbind(&mut self, button: Button) -> Option<A> {
+        let button = button.any_gamepad();
+        self.order.retain(|&b| b != button);
+        self.bindings.remove(&button).map(|binding| binding.action)
+    }
+
+    /// Returns the buttons bound to an action in the base layer,
+    /// in the order they were bound, e.g. primary and secondary.
+    pub fn buttons(&self, action: A) -> Vec<Button> {
+        self.order.iter().cloned()
+            .filter(|button| self.bindings[button].action == action)
+            .collect()
+    }
+
+    /// Returns the first button bound to an action in the base layer
+    /// for a kind of device, e.g. to show in prompts.
+    pub fn button_for(&self, action: A, kind: DeviceKind) -> Option<Button> {
+        self.buttons(action).into_iter().find(|button| match *button {
+            Button::Keyboard(_) => kind == DeviceKind::Keyboard,
+            Button::Mouse(_) => kind == DeviceKind::Mouse,
+            Button::Gamepad(_, _) => kind == DeviceKind::Gamepad,
+            Button::Stylus(_) => false,
+        })
+    }
+
+    /// Calls `callback` with the events of an action, for applications
+    /// that prefer handlers to matching on events.
+    ///
+    /// Callbacks are called in the order they were registered, when
+    /// `handle_input` or `update` produce an event of the action.
+    /// Clones of the action map share the callbacks.
+    pub fn on_action<F>(&mut self, action: A, callback: F)
+        where F: FnMut(&ActionEvent<A>) + 'static
+    {
+        self.callbacks.0.push((action, Rc::new(RefCell::new(callback))));
+    }
+
+    /// Removes the callbacks of an action.
+    pub fn remove_callbacks(&mut self, action: A) {
+        self.callbacks.0.retain(|&(a, _)| a != action);
+    }
+
+    fn dispatch(&self, event: &ActionEvent<A>) {
+        let action = event.action();
+        for &(a, ref callback) in &self.callbacks.0 {
+            if a == action { (&mut *callback.borrow_mut())(event); }
+        }
+    }
+
+    fn insert(&mut self, button: Button, binding: Binding<A>) {
+        let button = button.any_gamepad();
+        if self.bindings.insert(button, binding).is_none() {
+            self.order.push(button);
+        }
+    }
+
+    /// Removes a binding from the layer of `shift`.
+    pub fn unbind_layer(&mut self, shift: Button, button: Button) -> Option<A> {
+        let (shift, button) = (shift.any_gamepad(), button.any_gamepad());
+        self.layers.iter_mut()
+            .find(|layer| layer.shift == shift)
+            .and_then(|layer| layer.bindings.remove(&button))
+            .map(|binding| binding.action)
+    }
+
+    /// Returns the shift button of the active layer, if any.
+    pub fn active_layer(&self) -> Option<Button> {
+        self.layers.iter().rev()
+            .find(|layer| self.is_held(layer.shift))
+            .map(|layer| layer.shift)
+    }
+
+    // Returns `true` if a bound button is held on any mapped device.
+    fn is_held(&self, button: Button) -> bool {
+        self.held.iter().any(|held| held.any_gamepad() == button)
+    }
+
+    // Returns `false` for buttons of gamepads that are not mapped.
+    fn is_mapped(&self, button: Button) -> bool {
+        match (self.gamepad, button) {
+            (Some(gamepad), Button::Gamepad(id, _)) => id == gamepad,
+            _ => true,
+        }
+    }
+
+    /// Returns the action a button is bound to in the active layers.
+    pub fn resolve(&self, button: Button) -> Option<A> {
+        self.binding(button).map(|binding| binding.action)
+    }
+
+    fn binding(&self, button: Button) -> Option<Binding<A>> {
+        let button = button.any_gamepad();
+        for layer in self.layers.iter().rev() {
+            if !self.is_held(layer.shift) { continue; }
+            if let Some(&binding) = layer.bindings.get(&button) {
+                return Some(binding);
+            }
+        }
+        self.bindings.get(&button).cloned()
+    }
+
+    /// Returns `true` if an action is started by a held button.
+    pub fn is_pressed(&self, action: A) -> bool {
+        self.pressed.values().any(|&a| a == action)
+    }
+
+    /// Advances time, triggering hold actions
+    /// and canceling release actions held too long.
+    pub fn update(&mut self, dt: f64) -> Vec<ActionEvent<A>> {
+        let mut events = vec![];
+        let mut done = vec![];
+        for (&button, &mut (binding, ref mut time)) in &mut self.pending {
+            *time += dt;
+            match binding.trigger {
+                Trigger::TapHold(hold, threshold) if *time > threshold => {
+                    done.push(button);
+                    let pressed = self.pressed.values().any(|&a| a == hold);
+                    self.pressed.insert(button, hold);
+                    if !pressed { events.push(ActionEvent::Press(hold)); }
+                }
+                Trigger::Release(Some(max_hold)) if *time > max_hold => {
+                    done.push(button);
+                }
+                _ => {}
+            }
+        }
+        for button in done {
+            self.pending.remove(&button);
+        }
+        for event in &events {
+            self.dispatch(event);
+        }
+        events
+    }
+
+    /// Binds a button to tap an action on release, e.g. for charged attacks.
+    ///
+    /// With `max_hold`, holding the button longer than the seconds
+    /// cancels the action.
+    pub fn bind_release(&mut self, button: Button, action: A, max_hold: Option<f64>) {
+        self.insert(button, Binding {
+            action: action,
+            trigger: Trigger::Release(max_hold),
+            consume: true,
+        });
+    }
+
+    /// Maps an input to an action event.
+    ///
+    /// A button releases the action it started,
+    /// even if the active layer changed in the meantime.
+    pub fn handle_input(&mut self, input: &Input) -> Option<ActionEvent<A>> {
+        self.handle(input).0
+    }
+
+    // Also returns whether the input was consumed.
+    fn handle(&mut self, input: &Input) -> (Option<ActionEvent<A>>, bool) {
+        let (event, consumed) = self.map(input);
+        if let Some(ref event) = event {
+            self.dispatch(event);
+        }
+        (event, consumed)
+    }
+
+    fn map(&mut self, input: &Input) -> (Option<ActionEvent<A>>, bool) {
+        match *input {
+            Input::Press(button) | Input::Release(button)
+                if !self.is_mapped(button) => (None, false),
+            Input::Press(button) => {
+                if !self.held.insert(button) {
+                    // Repeats are consumed like the first press.
+                    let consume = match self.pending.get(&button) {
+                        Some(&(binding, _)) => binding.consume,
+                        None => self.binding(button).map_or(false, |binding| binding.consume),
+                    };
+                    return (None, consume);
+                }
+                match self.binding(button) {
+                    Some(binding) => match binding.trigger {
+                        Trigger::Press => {
+                            let pressed = self.is_pressed(binding.action);
+                            self.pressed.insert(button, binding.action);
+                            if pressed { return (None, binding.consume); }
+                            (Some(ActionEvent::Press(binding.action)), binding.consume)
+                        }
+                        Trigger::TapHold(..) | Trigger::Release(_) => {
+                            self.pending.insert(button, (binding, 0.0));
+                            (None, binding.consume)
+                        }
+                    },
+                    None => (None, false),
+                }
+            }
+            Input::Release(button) => {
+                self.held.remove(&button);
+                if let Some((binding, _)) = self.pending.remove(&button) {
+                    return (Some(ActionEvent::Tap(binding.action)), false);
+                }
+                match self.pressed.remove(&button) {
+                    // Other buttons still hold the action.
+                    Some(action) if self.is_pressed(action) => (None, false),
+                    action => (action.map(ActionEvent::Release), false),
+                }
+            }
+            _ => (None, false),
+        }
+    }
+}
+
+impl<A: Copy + Eq + Hash> Default for ActionMap<A> {
+    fn default() -> ActionMap<A> { ActionMap::new() }
+}
+
+/// Action maps of several contexts, e.g. menus and gameplay,
+/// handling input in order of priority.
+///
+/// A press bound in a context is not passed to lower priority contexts,
+/// unless bound with `bind_pass_through`. Releases are passed to all
+/// contexts, so actions never get stuck.
+#[derive(Clone, Debug)]
+pub struct ActionContexts<A> {
+    // Name, priority and action map, sorted by descending priority.
+    contexts: Vec<(String, i32, ActionMap<A>)>,
+}
+
+impl<A: Copy + Eq + Hash> ActionContexts<A> {
+    /// Creates new action contexts without contexts.
+    pub fn new() -> ActionContexts<A> {
+        ActionContexts { contexts: vec![] }
+    }
+
+    /// Adds a context, replacing a context with the same name.
+    ///
+    /// Contexts of equal priority handle input in the order they were added.
+    pub fn add(&mut self, name: &str, priority: i32, actions: ActionMap<A>) {
+        self.remove(name);
+        let i = self.contexts.iter()
+            .position(|&(_, p, _)| p < priority)
+            .unwrap_or(self.contexts.len());
+        self.contexts.insert(i, (name.to_string(), priority, actions));
+    }
+
+    /// Removes a context.
+    pub fn remove(&mut self, name: &str) -> Option<ActionMap<A>> {
+        self.contexts.iter()
+            .position(|&(ref n, _, _)| n == name)
+            .map(|i| self.contexts.remove(i).2)
+    }
+
+    /// Returns the action map of a context.
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut ActionMap<A>> {
+        self.contexts.iter_mut()
+            .find(|&&mut (ref n, _, _)| n == name)
+            .map(|&mut (_, _, ref mut actions)| actions)
+    }
+
+    /// Advances time in all contexts, triggering hold actions.
+    pub fn update(&mut self, dt: f64) -> Vec<ActionEvent<A>> {
+        let mut events = vec![];
+        for &mut (_, _, ref mut actions) in &mut self.contexts {
+            events.extend(actions.update(dt));
+        }
+        events
+    }
+
+    /// Maps an input to the action events of all contexts it reaches,
+    /// highest priority first.
+    pub fn handle_input(&mut self, input: &Input) -> Vec<ActionEvent<A>> {
+        let mut events = vec![];
+        for &mut (_, _, ref mut actions) in &mut self.contexts {
+            let (event, consumed) = actions.handle(input);
+            events.extend(event);
+            if consumed { break; }
+        }
+        events
+    }
+}
+
+impl<A: Copy + Eq + Hash> Default for ActionContexts<A> {
+    fn default() -> ActionContexts<A> { ActionContexts::new() }
+}
+
+/// Remembers recent action presses until gameplay code is ready
+/// to consume them, e.g. a jump pressed just before landing.
+#[derive(Clone, Debug)]
+pub struct ActionBuffer<A> {
+    /// Seconds an action press is remembered.
+    pub window: f64,
+    // Buffered actions and seconds since pressed, oldest first.
+    buffered: Vec<(A, f64)>,
+}
+
+impl<A: Copy + Eq> ActionBuffer<A> {
+    /// Creates a new buffer remembering presses for `window` seconds.
+    pub fn new(window: f64) -> ActionBuffer<A> {
+        ActionBuffer {
+            window: window,
+            buffered: vec![],
+        }
+    }
+
+    /// Buffers presses and taps of actions.
+    pub fn handle_event(&mut self, event: &ActionEvent<A>) {
+        match *event {
+            ActionEvent::Press(action) | ActionEvent::Tap(action) =>
+                self.buffered.push((action, 0.0)),
+            ActionEvent::Release(_) => {}
+        }
+    }
+
+    /// Advances time and forgets presses older than the window.
+    pub fn update(&mut self, dt: f64) {
+        let window = self.window;
+        for &mut (_, ref mut time) in &mut self.buffered {
+            *time += dt;
+        }
+        self.buffered.retain(|&(_, time)| time <= window);
+    }
+
+    /// Returns `true` if an action press is buffered.
+    pub fn is_buffered(&self, action: A) -> bool {
+        self.buffered.iter().any(|&(a, _)| a == action)
+    }
+
+    /// Consumes the oldest buffered press of an action.
+    ///
+    /// Returns `false` if the action is not buffered.
+    pub fn consume(&mut self, action: A) -> bool {
+        match self.buffered.iter().position(|&(a, _)| a == action) {
+            Some(i) => { self.buffered.remove(i); true }
+            None => false,
+        }
+    }
+
+    /// Forgets all buffered presses.
+    pub fn clear(&mut self) {
+        self.buffered.clear();
+    }
+}
+
+/// Detects buttons pressed simultaneously, within a tolerance,
+/// e.g. for chorded shortcuts.
+///
+/// Presses further apart than the tolerance do not form a chord.
+/// Chords of gamepad buttons are pad-independent, but all buttons
+/// of a chord must be pressed on the same gamepad.
+#[derive(Clone, Debug)]
+pub struct Chords<A> {
+    /// Maximum seconds between the first and last press of a chord.
+    pub tolerance: f64,
+    chords: Vec<(Vec<Button>, A)>,
+    time: f64,
+    // Held buttons and the time they were pressed.
+    held: HashMap<Button, f64>,
+}
+
+impl<A: Copy> Chords<A> {
+    /// Creates a new detector of presses at most `tolerance` seconds apart.
+    pub fn new(tolerance: f64) -> Chords<A> {
+        Chords {
+            tolerance: tolerance,
+            chords: vec![],
+            time: 0.0,
+            held: HashMap::new(),
+        }
+    }
+
+    /// Adds a chord of buttons triggering an action.
+    pub fn add(&mut self, buttons: &[Button], action: A) {
+        let buttons = buttons.iter().map(|button| button.any_gamepad()).collect();
+        self.chords.push((buttons, action));
+    }
+
+    /// Advances time.
+    pub fn update(&mut self, dt: f64) {
+        self.time += dt;
+    }
+
+    /// Tracks presses and releases.
+    ///
+    /// Returns the action of a chord completed by a press.
+    /// If several chords complete, the one with most buttons is used.
+    pub fn handle_input(&mut self, input: &Input) -> Option<A> {
+        match *input {
+            Input::Press(button) => {
+                if self.held.contains_key(&button) { return None; }
+                self.held.insert(button, self.time);
+                let gamepad = button.as_gamepad().map(|(id, _)| id).unwrap_or(0);
+                let mut found: Option<&(Vec<Button>, A)> = None;
+                for chord in &self.chords {
+                    if !chord.0.contains(&button.any_gamepad()) { continue; }
+                    if found.map(|f| f.0.len() >= chord.0.len()).unwrap_or(false) {
+                        continue;
+                    }
+                    let times: Option<Vec<f64>> = chord.0.iter()
+                        .map(|b| self.held.get(&b.with_gamepad(gamepad)).cloned()).collect();
+                    if let Some(times) = times {
+                        let first = times.iter().cloned().fold(self.time, f64::min);
+                        if self.time - first <= self.tolerance {
+                            found = Some(chord);
+                        }
+                    }
+                }
+                found.map(|&(_, action)| action)
+            }
+            Input::Release(button) => {
+                self.held.remove(&button);
+                None
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use { Button, GamepadButton, Input };
+    use active::DeviceKind;
+    use keyboard::Key;
+
+    #[test]
+    fn test_layers() {
+        let shift = Button::Keyboard(Key::LShift);
+        let w = Button::Keyboard(Key::W);
+        let mut actions = ActionMap::new();
+        actions.bind(w, "walk");
+        actions.bind_layer(shift, w, "sprint");
+
+        assert_eq!(actions.handle_input(&Input::Press(w)), Some(ActionEvent::Press("walk")));
+        assert_eq!(actions.handle_input(&Input::Press(shift)), None);
+        assert_eq!(actions.active_layer(), Some(shift));
+        assert_eq!(actions.handle_input(&Input::Release(w)), Some(ActionEvent::Release("walk")));
+        assert_eq!(actions.handle_input(&Input::Press(w)), Some(ActionEvent::Press("sprint")));
+        actions.handle_input(&Input::Release(shift));
+        assert_eq!(actions.handle_input(&Input::Release(w)), Some(ActionEvent::Release("sprint")));
+    }
+
+    #[test]
+    fn test_consume() {
+        let esc = Button::Keyboard(Key::Escape);
+        let tab = Button::Keyboard(Key::Tab);
+        let mut ui = ActionMap::new();
+        ui.bind(esc, "close");
+        ui.bind_pass_through(tab, "hint");
+        let mut game = ActionMap::new();
+        game.bind(esc, "pause");
+        game.bind(tab, "map");
+        let mut contexts = ActionContexts::new();
+        contexts.add("game", 0, game);
+        contexts.add("ui", 10, ui);
+
+        assert_eq!(contexts.handle_input(&Input::Press(esc)),
+                   vec![ActionEvent::Press("close")]);
+        // Key repeat does not reach lower contexts.
+        assert_eq!(contexts.handle_input(&Input::Press(esc)), vec![]);
+        assert_eq!(contexts.handle_input(&Input::Press(tab)),
+                   vec![ActionEvent::Press("hint"), ActionEvent::Press("map")]);
+        assert_eq!(contexts.handle_input(&Input::Press(tab)), vec![]);
+        contexts.remove("ui");
+        assert_eq!(contexts.handle_input(&Input::Release(esc)), vec![]);
+    }
+
+    #[test]
+    fn test_tap_hold() {
+        let e = Button::Keyboard(Key::E);
+        let mut actions = ActionMap::new();
+        actions.bind_tap_hold(e, "use", "inspect", 0.3);
+
+        assert_eq!(actions.handle_input(&Input::Press(e)), None);
+        assert_eq!(actions.update(0.1), vec![]);
+        assert_eq!(actions.handle_input(&Input::Release(e)), Some(ActionEvent::Tap("use")));
+        actions.handle_input(&Input::Press(e));
+        assert_eq!(actions.update(0.5), vec![ActionEvent::Press("inspect")]);
+        assert_eq!(actions.handle_input(&Input::Release(e)),
+                   Some(ActionEvent::Release("inspect")));
+    }
+
+    #[test]
+    fn test_release() {
+        let f = Button::Keyboard(Key::F);
+        let mut actions = ActionMap::new();
+        actions.bind_release(f, "throw", Some(2.0));
+
+        assert_eq!(actions.handle_input(&Input::Press(f)), None);
+        actions.update(1.0);
+        assert_eq!(actions.handle_input(&Input::Release(f)), Some(ActionEvent::Tap("throw")));
+        actions.handle_input(&Input::Press(f));
+        actions.update(3.0);
+        assert_eq!(actions.handle_input(&Input::Release(f)), None);
+    }
+
+    #[test]
+    fn test_buffer() {
+        let mut buffer = ActionBuffer::new(0.2);
+        buffer.handle_event(&ActionEvent::Press("jump"));
+        buffer.update(0.1);
+        assert!(buffer.consume("jump"));
+        assert!(!buffer.consume("jump"));
+        buffer.handle_event(&ActionEvent::Press("jump"));
+        buffer.update(0.3);
+        assert!(!buffer.is_buffered("jump"));
+    }
+
+    #[test]
+    fn test_chords() {
+        let lp = Button::Keyboard(Key::U);
+        let lk = Button::Keyboard(Key::J);
+        let mut chords = Chords::new(0.05);
+        chords.add(&[lp, lk], "throw");
+
+        assert_eq!(chords.handle_input(&Input::Press(lp)), None);
+        chords.update(0.02);
+        assert_eq!(chords.handle_input(&Input::Press(lk)), Some("throw"));
+        chords.handle_input(&Input::Release(lk));
+        chords.update(0.1);
+        assert_eq!(chords.handle_input(&Input::Press(lk)), None);
+    }
+
+    #[test]
+    fn test_alternates() {
+        let up = Button::Keyboard(Key::Up);
+        let w = Button::Keyboard(Key::W);
+        let south = Button::Gamepad(0, GamepadButton::South);
+        let mut actions = ActionMap::new();
+        actions.bind(up, "forward");
+        actions.bind(w, "forward");
+        actions.bind(south, "forward");
+        assert_eq!(actions.buttons("forward"), vec![up, w, south]);
+        assert_eq!(actions.button_for("forward", DeviceKind::Gamepad), Some(south));
+
+        assert_eq!(actions.handle_input(&Input::Press(up)), Some(ActionEvent::Press("forward")));
+        assert_eq!(actions.handle_input(&Input::Press(w)), None);
+        assert_eq!(actions.handle_input(&Input::Release(up)), None);
+        assert_eq!(actions.handle_input(&Input::Release(w)),
+                   Some(ActionEvent::Release("forward")));
+    }
+
+    #[test]
+    fn test_gamepads() {
+        let south = Button::Gamepad(0, GamepadButton::South);
+        let mut actions = ActionMap::new();
+        actions.bind(south, "jump");
+        assert_eq!(actions.handle_input(&Input::Press(Button::Gamepad(3, GamepadButton::South))),
+                   Some(ActionEvent::Press("jump")));
+        assert_eq!(actions.handle_input(&Input::Release(Button::Gamepad(3, GamepadButton::South))),
+                   Some(ActionEvent::Release("jump")));
+
+        actions.gamepad = Some(1);
+        assert_eq!(actions.handle_input(&Input::Press(Button::Gamepad(3, GamepadButton::South))),
+                   None);
+        assert_eq!(actions.handle_input(&Input::Press(Button::Gamepad(1, GamepadButton::South))),
+                   Some(ActionEvent::Press("jump")));
+        assert_eq!(actions.buttons("jump"), vec![south]);
+    }
+
+    #[test]
+    fn test_on_action() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let space = Button::Keyboard(Key::Space);
+        let e = Button::Keyboard(Key::E);
+        let events = Rc::new(RefCell::new(vec![]));
+        let mut actions = ActionMap::new();
+        actions.bind(space, "jump");
+        actions.bind_tap_hold(e, "use", "inspect", 0.3);
+        let jumps = events.clone();
+        actions.on_action("jump", move |event| jumps.borrow_mut().push(*event));
+        let inspects = events.clone();
+        actions.on_action("inspect", move |event| inspects.borrow_mut().push(*event));
+
+        actions.handle_input(&Input::Press(space));
+        actions.handle_input(&Input::Press(e));
+        actions.update(0.5);
+        actions.handle_input(&Input::Release(space));
+        assert_eq!(*events.borrow(), vec![ActionEvent::Press("jump"),
+                                          ActionEvent::Press("inspect"),
+                                          ActionEvent::Release("jump")]);
+
+        actions.remove_callbacks("jump");
+        actions.handle_input(&Input::Press(space));
+        assert_eq!(events.borrow().len(), 3);
+    }
+}
@@ -0,0 +1,150 @@
+//! Detection of the most recently used device.
+
+use { Button, Input, Motion };
+
+/// Kinds of input devices.
+#[derive(Copy, Clone, RustcDecodable, RustcEncodable, PartialEq, Eq, Hash, Debug)]
+pub enum DeviceKind {
+    /// A keyboard.
+    Keyboard,
+    /// A mouse.
+    Mouse,
+    /// A gamepad.
+    Gamepad,
+}
+
+/// Tracks which kind of device most recently produced meaningful input.
+///
+/// Useful for switching on-screen button prompts automatically.
+#[derive(Copy, Clone, Debug)]
+pub struct ActiveDevice {
+    /// Distance the mouse must move before it counts as used.
+    ///
+    /// Ignores small accidental movement, e.g. while typing.
+    /// The distance is measured from relative motion once the back-end
+    /// reported any, otherwise from the cursor position, so movement
+    /// reported both ways is counted once.
+    pub mouse_threshold: f64,
+    /// Absolute value a gamepad axis must reach before it counts as used.
+    ///
+    /// Ignores stick drift and noise.
+    pub axis_threshold: f64,
+    current: Option<DeviceKind>,
+    motion: f64,
+    cursor: Option<[f64; 2]>,
+    relative: bool,
+}
+
+impl ActiveDevice {
+    /// Creates a new tracker with no active device.
+    pub fn new(mouse_threshold: f64) -> ActiveDevice {
+        ActiveDevice {
+            mouse_threshold: mouse_threshold,
+            axis_threshold: 0.5,
+            current: None,
+            motion: 0.0,
+            cursor: None,
+            relative: false,
+        }
+    }
+
+    /// Returns the most recently used kind of device.
+    pub fn current(&self) -> Option<DeviceKind> {
+        self.current
+    }
+
+    /// Handles an input, returning the new kind of device when it changed.
+    pub fn handle_input(&mut self, input: &Input) -> Option<DeviceKind> {
+        let kind = match *input {
+            Input::Press(Button::Keyboard(_))
+          | Input::Text(_) => Some(DeviceKind::Keyboard),
+            Input::Press(Button::Mouse(_))
+          | Input::Move(Motion::MouseScroll(_, _)) => Some(DeviceKind::Mouse),
+            Input::Press(Button::Gamepad(_, _)) => Some(DeviceKind::Gamepad),
+            Input::Move(Motion::GamepadAxis(_, _, value)) => {
+                if value.abs() >= self.axis_threshold {
+                    Some(DeviceKind::Gamepad)
+                } else {
+                    None
+                }
+            }
+            Input::Move(Motion::MouseCursor(x, y)) => {
+                let distance = match self.cursor {
+                    Some([px, py]) => ((x - px).powi(2) + (y - py).powi(2)).sqrt(),
+                    None => 0.0,
+                };
+                self.cursor = Some([x, y]);
+                if self.relative { return None; }
+                self.moved(distance)
+            }
+            Input::Move(Motion::MouseRelative(dx, dy)) => {
+                self.relative = true;
+                self.moved((dx * dx + dy * dy).sqrt())
+            }
+            _ => None,
+        };
+        let kind = match kind {
+            Some(kind) => kind,
+            None => return None,
+        };
+        self.motion = 0.0;
+        if self.current == Some(kind) { return None; }
+        self.current = Some(kind);
+        Some(kind)
+    }
+
+    fn moved(&mut self, distance: f64) -> Option<DeviceKind> {
+        if self.current == Some(DeviceKind::Mouse) { return None; }
+        self.motion += distance;
+        if self.motion >= self.mouse_threshold {
+            Some(DeviceKind::Mouse)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use { GamepadAxis, GamepadButton, Key };
+
+    #[test]
+    fn test_switch() {
+        let mut active = ActiveDevice::new(10.0);
+        assert_eq!(active.current(), None);
+        assert_eq!(active.handle_input(&Input::Press(Button::Keyboard(Key::A))),
+                   Some(DeviceKind::Keyboard));
+        assert_eq!(active.handle_input(&Input::Text("a".to_string())), None);
+        let stick = |value| Input::Move(Motion::GamepadAxis(0, GamepadAxis::LeftStickX, value));
+        assert_eq!(active.handle_input(&stick(0.1)), None);
+        assert_eq!(active.handle_input(&stick(0.9)), Some(DeviceKind::Gamepad));
+        assert_eq!(active.handle_input(&Input::Press(Button::Gamepad(0, GamepadButton::South))),
+                   None);
+        assert_eq!(active.current(), Some(DeviceKind::Gamepad));
+    }
+
+    #[test]
+    fn test_cursor_threshold() {
+        let mut active = ActiveDevice::new(10.0);
+        active.handle_input(&Input::Press(Button::Keyboard(Key::A)));
+        assert_eq!(active.handle_input(&Input::Move(Motion::MouseCursor(0.0, 0.0))), None);
+        assert_eq!(active.handle_input(&Input::Move(Motion::MouseCursor(6.0, 0.0))), None);
+        assert_eq!(active.handle_input(&Input::Move(Motion::MouseCursor(6.0, 6.0))),
+                   Some(DeviceKind::Mouse));
+    }
+
+    #[test]
+    fn test_relative_and_cursor() {
+        let mut active = ActiveDevice::new(10.0);
+        active.handle_input(&Input::Press(Button::Keyboard(Key::A)));
+        active.handle_input(&Input::Move(Motion::MouseCursor(0.0, 0.0)));
+        for i in 1..3 {
+            assert_eq!(active.handle_input(&Input::Move(Motion::MouseRelative(3.0, 0.0))), None);
+            assert_eq!(active.handle_input(&Input::Move(Motion::MouseCursor(3.0 * i as f64, 0.0))),
+                       None);
+        }
+        assert_eq!(active.handle_input(&Input::Move(Motion::MouseRelative(4.0, 0.0))),
+                   Some(DeviceKind::Mouse));
+    }
+}
@@ -0,0 +1,93 @@
+//! Stable checksums of input, e.g. for desync detection.
+//!
+//! Checksums use FNV-1a with integers hashed as little-endian bytes,
+//! and `usize` and `isize` widened to 64 bits, so they are the same
+//! on every machine. `input_checksum` hashes the wire encoding and is
+//! also the same across versions of Rust. `checksum` relies on the
+//! `Hash` impls of the standard library, which may change between
+//! versions of Rust, so peers comparing them must use the same compiler.
+
+use std::hash::{ Hash, Hasher };
+
+use Input;
+use wire::Encoder;
+
+/// A 64 bit FNV-1a hasher.
+#[derive(Copy, Clone, Debug)]
+pub struct FnvHasher(u64);
+
+impl FnvHasher {
+    /// Creates a new hasher.
+    pub fn new() -> FnvHasher {
+        FnvHasher(0xcbf29ce484222325)
+    }
+}
+
+impl Default for FnvHasher {
+    fn default() -> FnvHasher { FnvHasher::new() }
+}
+
+impl Hasher for FnvHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 ^= b as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+
+    fn write_u16(&mut self, x: u16) { self.write(&x.to_le_bytes()) }
+    fn write_u32(&mut self, x: u32) { self.write(&x.to_le_bytes()) }
+    fn write_u64(&mut self, x: u64) { self.write(&x.to_le_bytes()) }
+    fn write_u128(&mut self, x: u128) { self.write(&x.to_le_bytes()) }
+    fn write_usize(&mut self, x: usize) { self.write_u64(x as u64) }
+    fn write_i16(&mut self, x: i16) { self.write_u16(x as u16) }
+    fn write_i32(&mut self, x: i32) { self.write_u32(x as u32) }
+    fn write_i64(&mut self, x: i64) { self.write_u64(x as u64) }
+    fn write_i128(&mut self, x: i128) { self.write_u128(x as u128) }
+    fn write_isize(&mut self, x: isize) { self.write_i64(x as i64) }
+}
+
+/// Returns the checksum of a value, e.g. the digested input
+/// of a player in a frame.
+///
+/// The result only depends on the value and the version of Rust.
+pub fn checksum<T: Hash>(value: &T) -> u64 {
+    let mut hasher = FnvHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Returns the checksum of raw inputs, e.g. of a recorded frame.
+pub fn input_checksum(inputs: &[Input]) -> u64 {
+    let mut buf = vec![];
+    let mut encoder = Encoder::new();
+    for input in inputs {
+        encoder.encode(0.0, input, &mut buf);
+    }
+    let mut hasher = FnvHasher::new();
+    hasher.write(&buf);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checksum() {
+        let mut hasher = FnvHasher::new();
+        hasher.write(b"a");
+        assert_eq!(hasher.finish(), 0xaf63dc4c8601ec8c);
+        assert_eq!(checksum(&(1u32, 2u32)), checksum(&(1u32, 2u32)));
+        assert!(checksum(&(1u32, 2u32)) != checksum(&(2u32, 1u32)));
+        // Little-endian and widened on every machine.
+        assert_eq!(checksum(&1usize), checksum(&1u64));
+        let mut hasher = FnvHasher::new();
+        hasher.write(&[1, 0, 0, 0]);
+        assert_eq!(checksum(&1u32), hasher.finish());
+    }
+}
@@ -0,0 +1,142 @@
+//! Sources of time.
+//!
+//! Time dependent helpers in this crate are advanced with `update(dt)`.
+//! A clock computes the time in seconds from which `dt` is taken,
+//! and can be replaced by a `TestClock` to get deterministic timing.
+//!
+//! Timestamps from devices or back-ends with their own epoch
+//! are made comparable by rebasing them onto one clock with `Rebase`.
+
+use std::time::Instant;
+
+/// A source of time in seconds.
+pub trait Clock {
+    /// Returns the current time in seconds.
+    fn now(&self) -> f64;
+}
+
+/// A monotonic clock measuring time since it was created.
+#[derive(Copy, Clone, Debug)]
+pub struct MonotonicClock {
+    start: Instant,
+}
+
+impl MonotonicClock {
+    /// Creates a new clock starting at zero.
+    pub fn new() -> MonotonicClock {
+        MonotonicClock { start: Instant::now() }
+    }
+}
+
+impl Default for MonotonicClock {
+    fn default() -> MonotonicClock { MonotonicClock::new() }
+}
+
+impl Clock for MonotonicClock {
+    fn now(&self) -> f64 {
+        let elapsed = self.start.elapsed();
+        elapsed.as_secs() as f64 + elapsed.subsec_nanos() as f64 * 1e-9
+    }
+}
+
+/// A clock that is only advanced manually, for tests.
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct TestClock {
+    time: f64,
+}
+
+impl TestClock {
+    /// Creates a new clock starting at zero.
+    pub fn new() -> TestClock {
+        TestClock { time: 0.0 }
+    }
+
+    /// Advances the clock by `dt` seconds.
+    pub fn advance(&mut self, dt: f64) {
+        self.time += dt;
+    }
+
+    /// Sets the time of the clock in seconds.
+    pub fn set(&mut self, time: f64) {
+        self.time = time;
+    }
+}
+
+impl Clock for TestClock {
+    fn now(&self) -> f64 {
+        self.time
+    }
+}
+
+/// A clock reading the time from a function, e.g. one provided by the back-end.
+pub struct FnClock<F> {
+    now: F,
+}
+
+impl<F: Fn() -> f64> FnClock<F> {
+    /// Creates a new clock returning the time in seconds from `now`.
+    pub fn new(now: F) -> FnClock<F> {
+        FnClock { now: now }
+    }
+}
+
+impl<F: Fn() -> f64> Clock for FnClock<F> {
+    fn now(&self) -> f64 {
+        (self.now)()
+    }
+}
+
+/// Converts timestamps with another epoch, e.g. of a device,
+/// to the time of a local clock.
+///
+/// The offset between the clocks is estimated from timestamps and the
+/// local time they were received at. Delivery delays only increase the
+/// measured offset, so the smallest one seen is kept.
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct Rebase {
+    offset: Option<f64>,
+}
+
+impl Rebase {
+    /// Creates a new rebase without a known offset.
+    pub fn new() -> Rebase {
+        Rebase { offset: None }
+    }
+
+    /// Returns the estimated offset from the other clock to the local clock.
+    pub fn offset(&self) -> Option<f64> {
+        self.offset
+    }
+
+    /// Converts a timestamp received at the local time `now`.
+    pub fn rebase(&mut self, timestamp: f64, now: f64) -> f64 {
+        let offset = match self.offset {
+            Some(offset) => offset.min(now - timestamp),
+            None => now - timestamp,
+        };
+        self.offset = Some(offset);
+        timestamp + offset
+    }
+
+    /// Forgets the offset, e.g. after the device was reconnected.
+    pub fn reset(&mut self) {
+        self.offset = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rebase() {
+        let clock = FnClock::new(|| 10.5);
+        let mut rebase = Rebase::new();
+        assert_eq!(rebase.rebase(100.0, clock.now()), 10.5);
+        // Delivered late, so the offset is kept.
+        assert_eq!(rebase.rebase(100.25, 11.0), 10.75);
+        // Delivered faster than before.
+        assert_eq!(rebase.rebase(101.0, 11.0), 11.0);
+        assert_eq!(rebase.offset(), Some(-90.0));
+    }
+}
@@ -0,0 +1,91 @@
+//! Checks for back-ends producing inputs.
+//!
+//! Back-end authors can run recorded or generated inputs through
+//! these checks in their own tests.
+
+use std::collections::HashSet;
+use std::fmt;
+
+use { Button, Input };
+
+/// A violation of the expected input stream.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Violation {
+    /// A button was released without being pressed, at the index.
+    ReleaseWithoutPress(usize, Button),
+    /// Time went backwards at the index.
+    TimeNotMonotonic(usize),
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Violation::ReleaseWithoutPress(i, button) =>
+                write!(f, "input {}: {:?} released without being pressed", i, button),
+            Violation::TimeNotMonotonic(i) =>
+                write!(f, "input {}: time went backwards", i),
+        }
+    }
+}
+
+/// Checks that every release has a matching press.
+///
+/// Repeated presses are allowed, since back-ends may report key repeat
+/// as presses.
+pub fn check_press_release<'a, I>(inputs: I) -> Result<(), Violation>
+    where I: IntoIterator<Item = &'a Input>
+{
+    let mut pressed = HashSet::new();
+    for (i, input) in inputs.into_iter().enumerate() {
+        match *input {
+            Input::Press(button) => { pressed.insert(button); }
+            Input::Release(button) => {
+                if !pressed.remove(&button) {
+                    return Err(Violation::ReleaseWithoutPress(i, button));
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Checks that the times of inputs never decrease.
+pub fn check_monotonic<'a, I>(inputs: I) -> Result<(), Violation>
+    where I: IntoIterator<Item = &'a (f64, Input)>
+{
+    let mut last = None;
+    for (i, &(time, _)) in inputs.into_iter().enumerate() {
+        if let Some(last) = last {
+            if time < last { return Err(Violation::TimeNotMonotonic(i)); }
+        }
+        last = Some(time);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Key;
+
+    #[test]
+    fn test_press_release() {
+        let a = Button::Keyboard(Key::A);
+        let good = vec![Input::Press(a), Input::Press(a), Input::Release(a)];
+        assert_eq!(check_press_release(&good), Ok(()));
+        let bad = vec![Input::Press(a), Input::Release(a), Input::Release(a)];
+        assert_eq!(check_press_release(&bad), Err(Violation::ReleaseWithoutPress(2, a)));
+        assert_eq!(Violation::ReleaseWithoutPress(2, a).to_string(),
+                   "input 2: Keyboard(A) released without being pressed");
+    }
+
+    #[test]
+    fn test_monotonic() {
+        let focus = Input::Focus(true);
+        let good = vec![(0.0, focus.clone()), (0.0, focus.clone()), (1.0, focus.clone())];
+        assert_eq!(check_monotonic(&good), Ok(()));
+        let bad = vec![(0.0, focus.clone()), (1.0, focus.clone()), (0.5, focus.clone())];
+        assert_eq!(check_monotonic(&bad), Err(Violation::TimeNotMonotonic(2)));
+    }
+}
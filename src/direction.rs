@@ -0,0 +1,202 @@
+//! Discrete directions of sticks and directional pads.
+
+use std::f64::consts::PI;
+
+use { Button, Input };
+
+/// Quantizes a 2D stick position into a number of directions,
+/// e.g. 8 for classic movement or the items of a radial menu.
+///
+/// Direction 0 points along the X axis, and directions increase
+/// toward the Y axis.
+#[derive(Copy, Clone, Debug)]
+pub struct Quantizer {
+    /// Fraction of a sector the stick must move past a boundary
+    /// before the direction changes, which avoids flickering.
+    pub hysteresis: f64,
+    /// Positions below the dead zone have no direction.
+    pub dead_zone: f64,
+    directions: u32,
+    direction: Option<u32>,
+}
+
+impl Quantizer {
+    /// Creates a new quantizer for a number of directions.
+    ///
+    /// There is at least one direction.
+    pub fn new(directions: u32) -> Quantizer {
+        Quantizer {
+            hysteresis: 0.1,
+            dead_zone: 0.5,
+            directions: directions.max(1),
+            direction: None,
+        }
+    }
+
+    /// Returns the number of directions.
+    pub fn directions(&self) -> u32 {
+        self.directions
+    }
+
+    /// Sets the number of directions, at least one.
+    ///
+    /// Forgets the current direction, which is reported again
+    /// by the next `quantize`.
+    pub fn set_directions(&mut self, directions: u32) {
+        self.directions = directions.max(1);
+        self.direction = None;
+    }
+
+    /// Returns the current direction.
+    pub fn direction(&self) -> Option<u32> {
+        self.direction
+    }
+
+    /// Quantizes a stick position.
+    ///
+    /// Returns the new direction if the direction changed.
+    pub fn quantize(&mut self, position: [f64; 2]) -> Option<Option<u32>> {
+        let (x, y) = (position[0], position[1]);
+        let direction = if (x * x + y * y).sqrt() <= self.dead_zone {
+            None
+        } else {
+            let sector = 2.0 * PI / self.directions as f64;
+            let angle = y.atan2(x);
+            // Angle in sectors from the center of direction 0.
+            let s = angle / sector;
+            let s = if s < 0.0 { s + self.directions as f64 } else { s };
+            match self.direction {
+                Some(current) if distance(s, current as f64, self.directions as f64)
+                    < 0.5 + self.hysteresis => Some(current),
+                _ => Some(s.round() as u32 % self.directions),
+            }
+        };
+        if direction == self.direction { return None; }
+        self.direction = direction;
+        Some(direction)
+    }
+}
+
+/// Detects double taps, e.g. of a direction for dashing.
+///
+/// Taps are button presses, or for sticks the directions entered
+/// from the center as reported by a `Quantizer`.
+#[derive(Clone, Debug)]
+pub struct DoubleTap<T> {
+    /// Maximum seconds between the two taps.
+    pub window: f64,
+    time: f64,
+    // The last tap and its time.
+    last: Option<(T, f64)>,
+    // Held buttons, whose repeated presses are not taps.
+    held: Vec<T>,
+}
+
+impl<T: Copy + PartialEq> DoubleTap<T> {
+    /// Creates a new detector of taps at most `window` seconds apart.
+    pub fn new(window: f64) -> DoubleTap<T> {
+        DoubleTap {
+            window: window,
+            time: 0.0,
+            last: None,
+            held: vec![],
+        }
+    }
+
+    /// Advances time.
+    pub fn update(&mut self, dt: f64) {
+        self.time += dt;
+    }
+
+    /// Registers a tap.
+    ///
+    /// Returns the tapped value if it was tapped twice within the window.
+    pub fn tap(&mut self, value: T) -> Option<T> {
+        match self.last {
+            Some((last, t)) if last == value && self.time - t <= self.window => {
+                // A third tap starts a new double tap.
+                self.last = None;
+                Some(value)
+            }
+            _ => {
+                self.last = Some((value, self.time));
+                None
+            }
+        }
+    }
+}
+
+impl DoubleTap<Button> {
+    /// Registers button presses as taps.
+    ///
+    /// Repeated presses of a held button, e.g. from key repeat,
+    /// are not taps.
+    /// Returns the button if it was pressed twice within the window.
+    pub fn handle_input(&mut self, input: &Input) -> Option<Button> {
+        match *input {
+            Input::Press(button) => {
+                if self.held.contains(&button) { return None; }
+                self.held.push(button);
+                self.tap(button)
+            }
+            Input::Release(button) => {
+                self.held.retain(|&held| held != button);
+                None
+            }
+            Input::Focus(false) => {
+                self.held.clear();
+                None
+            }
+            _ => None,
+        }
+    }
+}
+
+// Distance of two positions on a circle of length `n`.
+fn distance(a: f64, b: f64, n: f64) -> f64 {
+    let d = (a - b).abs() % n;
+    d.min(n - d)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quantizer() {
+        let mut quantizer = Quantizer::new(4);
+        assert_eq!(quantizer.quantize([1.0, 0.0]), Some(Some(0)));
+        assert_eq!(quantizer.quantize([0.0, 1.0]), Some(Some(1)));
+        // Slightly past the boundary between 1 and 2.
+        assert_eq!(quantizer.quantize([-0.75, 0.7]), None);
+        assert_eq!(quantizer.quantize([-1.0, 0.0]), Some(Some(2)));
+        assert_eq!(quantizer.quantize([0.0, -1.0]), Some(Some(3)));
+        assert_eq!(quantizer.quantize([0.0, 0.0]), Some(None));
+
+        quantizer.quantize([0.0, 1.0]);
+        quantizer.set_directions(0);
+        assert_eq!(quantizer.directions(), 1);
+        assert_eq!(quantizer.quantize([0.0, 1.0]), Some(Some(0)));
+    }
+
+    #[test]
+    fn test_double_tap() {
+        use { Button, Input, Key };
+
+        let a = Button::Keyboard(Key::A);
+        let mut double_tap = DoubleTap::new(0.3);
+        assert_eq!(double_tap.handle_input(&Input::Press(a)), None);
+        // Key repeat while held is not a tap.
+        double_tap.update(0.1);
+        assert_eq!(double_tap.handle_input(&Input::Press(a)), None);
+        double_tap.handle_input(&Input::Release(a));
+        double_tap.update(0.1);
+        assert_eq!(double_tap.handle_input(&Input::Press(a)), Some(a));
+        double_tap.handle_input(&Input::Release(a));
+        // Too slow.
+        double_tap.handle_input(&Input::Press(a));
+        double_tap.handle_input(&Input::Release(a));
+        double_tap.update(0.5);
+        assert_eq!(double_tap.handle_input(&Input::Press(a)), None);
+    }
+}
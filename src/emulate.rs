@@ -0,0 +1,197 @@
+//! Emulation of devices with other devices.
+
+use { GamepadAxis, Input, Motion };
+
+/// Emulates an analog stick with relative mouse motion.
+///
+/// Mouse motion deflects the stick, which returns to the center
+/// when the mouse stops moving.
+#[derive(Copy, Clone, Debug)]
+pub struct MouseStick {
+    /// The id of the emulated gamepad.
+    pub id: u32,
+    /// The X and Y axes of the emulated stick.
+    pub axes: [GamepadAxis; 2],
+    /// Stick deflection per pixel of mouse motion.
+    pub sensitivity: f64,
+    /// Rate per second the stick returns to the center.
+    pub decay: f64,
+    position: [f64; 2],
+}
+
+impl MouseStick {
+    /// Creates a new emulated right stick of the gamepad `id`.
+    pub fn new(id: u32, sensitivity: f64, decay: f64) -> MouseStick {
+        MouseStick {
+            id: id,
+            axes: [GamepadAxis::RightStickX, GamepadAxis::RightStickY],
+            sensitivity: sensitivity,
+            decay: decay,
+            position: [0.0; 2],
+        }
+    }
+
+    /// Returns the position of the stick.
+    pub fn position(&self) -> [f64; 2] {
+        self.position
+    }
+
+    /// Deflects the stick on relative mouse motion.
+    ///
+    /// Returns the moves of the stick axes.
+    pub fn handle_input(&mut self, input: &Input) -> Vec<Input> {
+        if let Input::Move(Motion::MouseRelative(dx, dy)) = *input {
+            let x = self.position[0] + dx * self.sensitivity;
+            let y = self.position[1] + dy * self.sensitivity;
+            // Bound the magnitude to the unit circle.
+            let len = (x * x + y * y).sqrt();
+            let scale = if len > 1.0 { 1.0 / len } else { 1.0 };
+            self.set_position([x * scale, y * scale])
+        } else {
+            vec![]
+        }
+    }
+
+    /// Advances time, returning the stick to the center.
+    ///
+    /// Returns the moves of the stick axes.
+    pub fn update(&mut self, dt: f64) -> Vec<Input> {
+        if self.position == [0.0; 2] { return vec![]; }
+        let scale = (-self.decay * dt).exp();
+        let mut position = [self.position[0] * scale, self.position[1] * scale];
+        // Snap to the center instead of decaying forever.
+        if position[0].abs() < 1e-3 && position[1].abs() < 1e-3 {
+            position = [0.0; 2];
+        }
+        self.set_position(position)
+    }
+
+    fn set_position(&mut self, position: [f64; 2]) -> Vec<Input> {
+        let mut moves = vec![];
+        for i in 0..2 {
+            if position[i] != self.position[i] {
+                moves.push(Input::Move(Motion::GamepadAxis(self.id, self.axes[i], position[i])));
+            }
+        }
+        self.position = position;
+        moves
+    }
+}
+
+/// Emulates a mouse cursor with an analog stick.
+///
+/// The cursor moves faster the longer the stick is held deflected.
+#[derive(Copy, Clone, Debug)]
+pub struct StickCursor {
+    /// The id of the gamepad moving the cursor.
+    pub id: u32,
+    /// The X and Y axes of the stick moving the cursor.
+    pub axes: [GamepadAxis; 2],
+    /// Pixels per second at full deflection.
+    pub speed: f64,
+    /// Pixels per second added to the speed per second of deflection.
+    pub acceleration: f64,
+    /// Stick deflections below the dead zone do not move the cursor.
+    pub dead_zone: f64,
+    stick: [f64; 2],
+    position: [f64; 2],
+    size: [f64; 2],
+    // Seconds the stick has been deflected.
+    held: f64,
+}
+
+impl StickCursor {
+    /// Creates a new cursor moved by the left stick of the gamepad `id`,
+    /// starting at the center of a window.
+    pub fn new(id: u32, speed: f64, size: [u32; 2]) -> StickCursor {
+        let size = [size[0] as f64, size[1] as f64];
+        StickCursor {
+            id: id,
+            axes: [GamepadAxis::LeftStickX, GamepadAxis::LeftStickY],
+            speed: speed,
+            acceleration: 0.0,
+            dead_zone: 0.2,
+            stick: [0.0; 2],
+            position: [size[0] * 0.5, size[1] * 0.5],
+            size: size,
+            held: 0.0,
+        }
+    }
+
+    /// Returns the position of the cursor.
+    pub fn position(&self) -> [f64; 2] {
+        self.position
+    }
+
+    /// Tracks the stick and the window size.
+    pub fn handle_input(&mut self, input: &Input) {
+        match *input {
+            Input::Move(Motion::GamepadAxis(id, axis, value)) if id == self.id => {
+                if axis == self.axes[0] { self.stick[0] = value; }
+                if axis == self.axes[1] { self.stick[1] = value; }
+            }
+            Input::Resize(w, h) => {
+                self.size = [w as f64, h as f64];
+                self.position = self.clamp(self.position);
+            }
+            _ => {}
+        }
+    }
+
+    /// Advances time and moves the cursor.
+    ///
+    /// Returns the cursor motion if the cursor moved.
+    pub fn update(&mut self, dt: f64) -> Option<Input> {
+        let (x, y) = (self.stick[0], self.stick[1]);
+        let len = (x * x + y * y).sqrt();
+        if len <= self.dead_zone {
+            self.held = 0.0;
+            return None;
+        }
+        self.held += dt;
+        let speed = self.speed + self.acceleration * self.held;
+        // Rescale so the speed starts at 0 at the edge of the dead zone.
+        let scale = ((len - self.dead_zone) / (1.0 - self.dead_zone)).min(1.0) / len;
+        let d = speed * scale * dt;
+        let position = self.clamp([self.position[0] + x * d, self.position[1] + y * d]);
+        if position == self.position { return None; }
+        self.position = position;
+        Some(Input::Move(Motion::MouseCursor(position[0], position[1])))
+    }
+
+    fn clamp(&self, position: [f64; 2]) -> [f64; 2] {
+        [position[0].max(0.0).min(self.size[0]), position[1].max(0.0).min(self.size[1])]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use { GamepadAxis, Input, Motion };
+
+    #[test]
+    fn test_mouse_stick() {
+        let mut stick = MouseStick::new(1, 0.1, 10.0);
+        assert_eq!(stick.handle_input(&Input::Move(Motion::MouseRelative(5.0, 0.0))),
+                   vec![Input::Move(Motion::GamepadAxis(1, GamepadAxis::RightStickX, 0.5))]);
+        // Bound to the unit circle.
+        stick.handle_input(&Input::Move(Motion::MouseRelative(0.0, 100.0)));
+        let p = stick.position();
+        assert!(((p[0] * p[0] + p[1] * p[1]).sqrt() - 1.0).abs() < 1e-9);
+        assert_eq!(stick.handle_input(&Input::Move(Motion::MouseCursor(1.0, 1.0))), vec![]);
+        // Returns to the center.
+        assert_eq!(stick.update(1.0).len(), 2);
+        assert_eq!(stick.position(), [0.0, 0.0]);
+        assert_eq!(stick.update(1.0), vec![]);
+    }
+
+    #[test]
+    fn test_stick_cursor() {
+        let mut cursor = StickCursor::new(0, 100.0, [200, 100]);
+        cursor.dead_zone = 0.0;
+        cursor.handle_input(&Input::Move(Motion::GamepadAxis(0, GamepadAxis::LeftStickX, 1.0)));
+        assert_eq!(cursor.update(0.5), Some(Input::Move(Motion::MouseCursor(150.0, 50.0))));
+        assert_eq!(cursor.update(1.0), Some(Input::Move(Motion::MouseCursor(200.0, 50.0))));
+        assert_eq!(cursor.update(1.0), None);
+    }
+}
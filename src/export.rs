@@ -0,0 +1,195 @@
+//! Export of recorded input for analysis, e.g. in spreadsheets.
+
+use { Button, Input, Motion, PenHover, Touch };
+use recording::Macro;
+
+/// An input flattened into a structured record.
+#[derive(Clone, RustcDecodable, RustcEncodable, PartialEq, Debug)]
+pub struct Record {
+    /// Seconds since the start of the recording.
+    pub time: f64,
+    /// The device, e.g. `"keyboard"` or `"gamepad0"`.
+    pub device: String,
+    /// The element of the device, e.g. `"A"` or `"LeftStickX"`.
+    pub element: String,
+    /// The value, with components of positions separated by `;`.
+    pub value: String,
+    /// The kind of input, e.g. `"press"`.
+    pub kind: String,
+}
+
+impl Record {
+    /// Flattens an input at a time in seconds.
+    pub fn new(time: f64, input: &Input) -> Record {
+        let (device, element, value, kind) = match *input {
+            Input::Press(button) => {
+                let (device, element) = button_names(button);
+                (device, element, "1".to_string(), "press")
+            }
+            Input::Release(button) => {
+                let (device, element) = button_names(button);
+                (device, element, "0".to_string(), "release")
+            }
+            Input::Move(motion) => {
+                let (device, element, value) = match motion {
+                    Motion::MouseCursor(x, y) =>
+                        ("mouse".to_string(), "cursor".to_string(), format!("{};{}", x, y)),
+                    Motion::MouseRelative(x, y) =>
+                        ("mouse".to_string(), "relative".to_string(), format!("{};{}", x, y)),
+                    Motion::MouseScroll(x, y) =>
+                        ("mouse".to_string(), "scroll".to_string(), format!("{};{}", x, y)),
+                    Motion::GamepadAxis(id, axis, value) =>
+                        (format!("gamepad{}", id), format!("{:?}", axis), value.to_string()),
+                    Motion::GamepadGyro(id, v) => (format!("gamepad{}", id),
+                        "gyro".to_string(), format!("{};{};{}", v[0], v[1], v[2])),
+                    Motion::GamepadAccel(id, v) => (format!("gamepad{}", id),
+                        "accel".to_string(), format!("{};{};{}", v[0], v[1], v[2])),
+                };
+                (device, element, value, "move")
+            }
+            Input::Text(ref text) =>
+                ("keyboard".to_string(), String::new(), text.clone(), "text"),
+            Input::Resize(w, h) =>
+                ("window".to_string(), String::new(), format!("{};{}", w, h), "resize"),
+            Input::Focus(focus) =>
+                ("window".to_string(), String::new(), bool_value(focus), "focus"),
+            Input::OnScreenKeyboard(shown) =>
+                ("window".to_string(), String::new(), bool_value(shown), "on_screen_keyboard"),
+            Input::Touch(touch) => ("touch".to_string(), touch.id.to_string(),
+                format!("{};{}", touch.position[0], touch.position[1]),
+                phase_kind(touch)),
+            Input::PenHover(hover) => match hover {
+                PenHover::InRange =>
+                    ("stylus".to_string(), String::new(), String::new(), "hover_in"),
+                PenHover::Move(pos, _) => ("stylus".to_string(), String::new(),
+                    format!("{};{}", pos[0], pos[1]), "hover"),
+                PenHover::OutOfRange =>
+                    ("stylus".to_string(), String::new(), String::new(), "hover_out"),
+            },
+            Input::GamepadTouch(id, touch) => (format!("gamepad{}", id),
+                format!("touch{}", touch.id),
+                format!("{};{}", touch.position[0], touch.position[1]),
+                phase_kind(touch)),
+            Input::GamepadBattery(id, battery) => (format!("gamepad{}", id),
+                "battery".to_string(),
+                battery.percent.map(|p| p.to_string()).unwrap_or(String::new()),
+                "battery"),
+            Input::GamepadConnected(id, connected) => (format!("gamepad{}", id),
+                String::new(), bool_value(connected), "connected"),
+            Input::DrumHit(id, pad, velocity) => (format!("gamepad{}", id),
+                format!("{:?}", pad), velocity.to_string(), "drum_hit"),
+        };
+        Record {
+            time: time,
+            device: device,
+            element: element,
+            value: value,
+            kind: kind.to_string(),
+        }
+    }
+}
+
+/// Returns the records of a recorded macro.
+pub fn records(recorded: &Macro) -> Vec<Record> {
+    let mut time = 0.0;
+    recorded.events.iter().map(|&(delay, ref input)| {
+        time += delay;
+        Record::new(time, input)
+    }).collect()
+}
+
+/// Exports a recorded macro as CSV with a header line.
+pub fn to_csv(recorded: &Macro) -> String {
+    let mut csv = "time,device,element,value,kind\n".to_string();
+    for record in records(recorded) {
+        csv.push_str(&format!("{},{},{},{},{}\n", record.time,
+            csv_field(&record.device), csv_field(&record.element),
+            csv_field(&record.value), record.kind));
+    }
+    csv
+}
+
+/// Exports a recorded macro as newline-delimited JSON records.
+pub fn to_json_lines(recorded: &Macro) -> String {
+    let mut lines = String::new();
+    for record in records(recorded) {
+        lines.push_str(&format!(
+            "{{\"time\":{},\"device\":{},\"element\":{},\"value\":{},\"kind\":{}}}\n",
+            record.time, json_string(&record.device), json_string(&record.element),
+            json_string(&record.value), json_string(&record.kind)));
+    }
+    lines
+}
+
+fn button_names(button: Button) -> (String, String) {
+    match button {
+        Button::Keyboard(key) => ("keyboard".to_string(), format!("{:?}", key)),
+        Button::Mouse(button) => ("mouse".to_string(), format!("{:?}", button)),
+        Button::Stylus(button) => ("stylus".to_string(), format!("{:?}", button)),
+        Button::Gamepad(id, button) => (format!("gamepad{}", id), format!("{:?}", button)),
+    }
+}
+
+fn bool_value(x: bool) -> String {
+    if x { "1".to_string() } else { "0".to_string() }
+}
+
+fn phase_kind(touch: Touch) -> &'static str {
+    use touch::TouchPhase::*;
+
+    match touch.phase {
+        Began => "touch_began",
+        Moved => "touch_moved",
+        Stationary => "touch_stationary",
+        Ended => "touch_ended",
+        Cancelled => "touch_cancelled",
+    }
+}
+
+// Quotes a field containing separators, quotes or line breaks.
+fn csv_field(field: &str) -> String {
+    if field.contains(|c| c == ',' || c == '"' || c == '\n' || c == '\r') {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut json = "\"".to_string();
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use { Button, Input, Key };
+    use recording::Macro;
+
+    #[test]
+    fn test_csv() {
+        let mut recorded = Macro::new("a");
+        recorded.events.push((0.5, Input::Press(Button::Keyboard(Key::A))));
+        recorded.events.push((0.25, Input::Text("a,\"b\"".to_string())));
+        assert_eq!(to_csv(&recorded), "time,device,element,value,kind\n\
+                                       0.5,keyboard,A,1,press\n\
+                                       0.75,keyboard,,\"a,\"\"b\"\"\",text\n");
+    }
+
+    #[test]
+    fn test_json_lines() {
+        let mut recorded = Macro::new("a");
+        recorded.events.push((0.5, Input::Text("\"\n".to_string())));
+        assert_eq!(to_json_lines(&recorded), "{\"time\":0.5,\"device\":\"keyboard\",\
+            \"element\":\"\",\"value\":\"\\\"\\u000a\",\"kind\":\"text\"}\n");
+    }
+}
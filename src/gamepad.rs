@@ -0,0 +1,631 @@
+//! Back-end agnostic gamepad input.
+
+use std::collections::{ HashMap, HashSet };
+
+use { Button, Input, Motion };
+
+#[cfg(feature = "quickcheck")]
+use quickcheck::{ Arbitrary, Gen };
+
+/// Represent a gamepad button by its position on a standard layout.
+///
+/// Game code binds to positions rather than the letters or symbols
+/// printed on a particular vendor's buttons.
+#[derive(Copy, Clone, RustcDecodable, RustcEncodable, PartialEq,
+    Eq, Ord, PartialOrd, Hash, Debug)]
+#[non_exhaustive]
+pub enum GamepadButton {
+    /// The bottom face button.
+    South,
+    /// The right face button.
+    East,
+    /// The left face button.
+    West,
+    /// The top face button.
+    North,
+    /// The left shoulder button.
+    LeftShoulder,
+    /// The right shoulder button.
+    RightShoulder,
+    /// The left trigger.
+    LeftTrigger,
+    /// The right trigger.
+    RightTrigger,
+    /// The left center button, e.g. Back, Share or Minus.
+    Select,
+    /// The right center button, e.g. Start, Options or Plus.
+    Start,
+    /// Clicking the left stick.
+    LeftStick,
+    /// Clicking the right stick.
+    RightStick,
+    /// Up on the directional pad.
+    DPadUp,
+    /// Down on the directional pad.
+    DPadDown,
+    /// Left on the directional pad.
+    DPadLeft,
+    /// Right on the directional pad.
+    DPadRight,
+    /// Clicking the touchpad.
+    Touchpad,
+    /// The vendor button in the center, e.g. Xbox, PS or Home.
+    Guide,
+    /// The capture or share button for screenshots and clips.
+    Capture,
+    /// The microphone mute button.
+    MicMute,
+    /// The first back paddle.
+    Paddle1,
+    /// The second back paddle.
+    Paddle2,
+    /// The third back paddle.
+    Paddle3,
+    /// The fourth back paddle.
+    Paddle4,
+    /// A button unknown to this crate with the back-end's code,
+    /// passed through from the back-end.
+    Other(u32),
+}
+
+// All gamepad buttons.
+pub(crate) static BUTTONS: &'static [GamepadButton] = &[
+    GamepadButton::South, GamepadButton::East,
+    GamepadButton::West, GamepadButton::North,
+    GamepadButton::LeftShoulder, GamepadButton::RightShoulder,
+    GamepadButton::LeftTrigger, GamepadButton::RightTrigger,
+    GamepadButton::Select, GamepadButton::Start,
+    GamepadButton::LeftStick, GamepadButton::RightStick,
+    GamepadButton::DPadUp, GamepadButton::DPadDown,
+    GamepadButton::DPadLeft, GamepadButton::DPadRight,
+    GamepadButton::Touchpad, GamepadButton::Guide,
+    GamepadButton::Capture, GamepadButton::MicMute,
+    GamepadButton::Paddle1, GamepadButton::Paddle2,
+    GamepadButton::Paddle3, GamepadButton::Paddle4,
+];
+
+/// Gamepad vendors with their own button labels.
+#[derive(Copy, Clone, RustcDecodable, RustcEncodable, PartialEq, Eq, Hash, Debug)]
+pub enum Vendor {
+    /// Xbox style labels (A, B, X, Y).
+    Xbox,
+    /// PlayStation style labels (Cross, Circle, Square, Triangle).
+    PlayStation,
+    /// Nintendo style labels (B, A, Y, X).
+    Nintendo,
+}
+
+impl GamepadButton {
+    /// Returns all gamepad buttons, e.g. to list them in a binding UI.
+    pub fn all() -> &'static [GamepadButton] {
+        BUTTONS
+    }
+
+    /// Returns the label printed on the button by a vendor.
+    pub fn label(&self, vendor: Vendor) -> &'static str {
+        use self::GamepadButton::*;
+
+        match (vendor, *self) {
+            (Vendor::Xbox, South) => "A",
+            (Vendor::Xbox, East) => "B",
+            (Vendor::Xbox, West) => "X",
+            (Vendor::Xbox, North) => "Y",
+            (Vendor::Xbox, LeftShoulder) => "LB",
+            (Vendor::Xbox, RightShoulder) => "RB",
+            (Vendor::Xbox, LeftTrigger) => "LT",
+            (Vendor::Xbox, RightTrigger) => "RT",
+            (Vendor::Xbox, Select) => "View",
+            (Vendor::Xbox, Start) => "Menu",
+            (Vendor::Xbox, LeftStick) => "LS",
+            (Vendor::Xbox, RightStick) => "RS",
+            (Vendor::Xbox, Guide) => "Xbox",
+            (Vendor::Xbox, Capture) => "Share",
+            (Vendor::PlayStation, South) => "Cross",
+            (Vendor::PlayStation, East) => "Circle",
+            (Vendor::PlayStation, West) => "Square",
+            (Vendor::PlayStation, North) => "Triangle",
+            (Vendor::PlayStation, LeftShoulder) => "L1",
+            (Vendor::PlayStation, RightShoulder) => "R1",
+            (Vendor::PlayStation, LeftTrigger) => "L2",
+            (Vendor::PlayStation, RightTrigger) => "R2",
+            (Vendor::PlayStation, Select) => "Share",
+            (Vendor::PlayStation, Start) => "Options",
+            (Vendor::PlayStation, LeftStick) => "L3",
+            (Vendor::PlayStation, RightStick) => "R3",
+            (Vendor::PlayStation, Guide) => "PS",
+            (Vendor::PlayStation, Capture) => "Capture",
+            (Vendor::Nintendo, South) => "B",
+            (Vendor::Nintendo, East) => "A",
+            (Vendor::Nintendo, West) => "Y",
+            (Vendor::Nintendo, North) => "X",
+            (Vendor::Nintendo, LeftShoulder) => "L",
+            (Vendor::Nintendo, RightShoulder) => "R",
+            (Vendor::Nintendo, LeftTrigger) => "ZL",
+            (Vendor::Nintendo, RightTrigger) => "ZR",
+            (Vendor::Nintendo, Select) => "Minus",
+            (Vendor::Nintendo, Start) => "Plus",
+            (Vendor::Nintendo, LeftStick) => "LS",
+            (Vendor::Nintendo, RightStick) => "RS",
+            (Vendor::Nintendo, Guide) => "Home",
+            (Vendor::Nintendo, Capture) => "Capture",
+            (_, DPadUp) => "Up",
+            (_, DPadDown) => "Down",
+            (_, DPadLeft) => "Left",
+            (_, DPadRight) => "Right",
+            (_, Touchpad) => "Touchpad",
+            (_, MicMute) => "Mic",
+            (_, Paddle1) => "P1",
+            (_, Paddle2) => "P2",
+            (_, Paddle3) => "P3",
+            (_, Paddle4) => "P4",
+            (_, Other(_)) => "?",
+        }
+    }
+
+    /// Returns a vendor neutral name of the button for UIs.
+    pub fn name(&self) -> &'static str {
+        use self::GamepadButton::*;
+
+        match *self {
+            South => "South",
+            East => "East",
+            West => "West",
+            North => "North",
+            LeftShoulder => "Left Shoulder",
+            RightShoulder => "Right Shoulder",
+            LeftTrigger => "Left Trigger",
+            RightTrigger => "Right Trigger",
+            Select => "Select",
+            Start => "Start",
+            LeftStick => "Left Stick",
+            RightStick => "Right Stick",
+            DPadUp => "D-Pad Up",
+            DPadDown => "D-Pad Down",
+            DPadLeft => "D-Pad Left",
+            DPadRight => "D-Pad Right",
+            Touchpad => "Touchpad",
+            Guide => "Guide",
+            Capture => "Capture",
+            MicMute => "Mic Mute",
+            Paddle1 => "Paddle 1",
+            Paddle2 => "Paddle 2",
+            Paddle3 => "Paddle 3",
+            Paddle4 => "Paddle 4",
+            Other(_) => "Other",
+        }
+    }
+
+    /// Returns the x and y axes of the stick clicked by a stick button.
+    pub fn stick_axes(&self) -> Option<[GamepadAxis; 2]> {
+        match *self {
+            GamepadButton::LeftStick =>
+                Some([GamepadAxis::LeftStickX, GamepadAxis::LeftStickY]),
+            GamepadButton::RightStick =>
+                Some([GamepadAxis::RightStickX, GamepadAxis::RightStickY]),
+            _ => None,
+        }
+    }
+
+    /// Returns the button with a vendor's label.
+    pub fn from_label(vendor: Vendor, label: &str) -> Option<GamepadButton> {
+        BUTTONS.iter().cloned().find(|button| button.label(vendor) == label)
+    }
+
+    /// Returns the button with a vendor neutral name, ignoring case.
+    pub fn from_name(name: &str) -> Option<GamepadButton> {
+        BUTTONS.iter().cloned().find(|button| button.name().eq_ignore_ascii_case(name))
+    }
+}
+
+/// Represent an absolute gamepad axis.
+#[derive(Copy, Clone, RustcDecodable, RustcEncodable, PartialEq,
+    Eq, Ord, PartialOrd, Hash, Debug)]
+#[non_exhaustive]
+pub enum GamepadAxis {
+    /// Horizontal position of the left stick, positive to the right.
+    LeftStickX,
+    /// Vertical position of the left stick, positive downwards.
+    LeftStickY,
+    /// Horizontal position of the right stick, positive to the right.
+    RightStickX,
+    /// Vertical position of the right stick, positive downwards.
+    RightStickY,
+    /// How far the left trigger is pressed.
+    LeftTrigger,
+    /// How far the right trigger is pressed.
+    RightTrigger,
+    /// An axis unknown to this crate with the back-end's code,
+    /// passed through from the back-end.
+    Other(u32),
+}
+
+// All gamepad axes.
+pub(crate) static AXES: &'static [GamepadAxis] = &[
+    GamepadAxis::LeftStickX, GamepadAxis::LeftStickY,
+    GamepadAxis::RightStickX, GamepadAxis::RightStickY,
+    GamepadAxis::LeftTrigger, GamepadAxis::RightTrigger,
+];
+
+impl GamepadAxis {
+    /// Returns all gamepad axes, e.g. to list them in a binding UI.
+    pub fn all() -> &'static [GamepadAxis] {
+        AXES
+    }
+
+    /// Returns a name of the axis for UIs.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            GamepadAxis::LeftStickX => "Left Stick X",
+            GamepadAxis::LeftStickY => "Left Stick Y",
+            GamepadAxis::RightStickX => "Right Stick X",
+            GamepadAxis::RightStickY => "Right Stick Y",
+            GamepadAxis::LeftTrigger => "Left Trigger",
+            GamepadAxis::RightTrigger => "Right Trigger",
+            GamepadAxis::Other(_) => "Other",
+        }
+    }
+
+    /// Returns the axis with a name, ignoring case.
+    pub fn from_name(name: &str) -> Option<GamepadAxis> {
+        AXES.iter().cloned().find(|axis| axis.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Returns the minimum and maximum value of the axis.
+    ///
+    /// Sticks range from -1 to 1, and triggers from 0 to 1.
+    pub fn range(&self) -> [f64; 2] {
+        match *self {
+            GamepadAxis::LeftTrigger | GamepadAxis::RightTrigger => [0.0, 1.0],
+            _ => [-1.0, 1.0],
+        }
+    }
+
+    /// Returns the button clicking the stick of a stick axis.
+    pub fn stick_button(&self) -> Option<GamepadButton> {
+        match *self {
+            GamepadAxis::LeftStickX | GamepadAxis::LeftStickY =>
+                Some(GamepadButton::LeftStick),
+            GamepadAxis::RightStickX | GamepadAxis::RightStickY =>
+                Some(GamepadButton::RightStick),
+            _ => None,
+        }
+    }
+
+    /// Returns the button of a trigger axis.
+    pub fn trigger_button(&self) -> Option<GamepadButton> {
+        match *self {
+            GamepadAxis::LeftTrigger => Some(GamepadButton::LeftTrigger),
+            GamepadAxis::RightTrigger => Some(GamepadButton::RightTrigger),
+            _ => None,
+        }
+    }
+}
+
+/// The battery state of a wireless gamepad.
+#[derive(Copy, Clone, RustcDecodable, RustcEncodable, PartialEq, Eq, Hash, Debug)]
+pub struct Battery {
+    /// The charge from 0 to 100 percent, if known.
+    pub percent: Option<u8>,
+    /// Whether the battery is charging.
+    pub charging: bool,
+}
+
+/// Derives trigger button presses from analog trigger axes.
+///
+/// For back-ends that only report triggers as axes.
+#[derive(Clone, Debug)]
+pub struct TriggerButtons {
+    /// The axis value at which a trigger counts as pressed.
+    pub threshold: f64,
+    pressed: HashSet<(u32, GamepadAxis)>,
+}
+
+impl TriggerButtons {
+    /// Creates a new trigger button stage.
+    pub fn new(threshold: f64) -> TriggerButtons {
+        TriggerButtons {
+            threshold: threshold,
+            pressed: HashSet::new(),
+        }
+    }
+
+    /// Handles an input, returning a press or release of a trigger button
+    /// when its axis crosses the threshold.
+    pub fn handle_input(&mut self, input: &Input) -> Option<Input> {
+        if let Input::Move(Motion::GamepadAxis(id, axis, value)) = *input {
+            if let Some(button) = axis.trigger_button() {
+                let button = Button::Gamepad(id, button);
+                if value >= self.threshold {
+                    if self.pressed.insert((id, axis)) {
+                        return Some(Input::Press(button));
+                    }
+                } else if self.pressed.remove(&(id, axis)) {
+                    return Some(Input::Release(button));
+                }
+            }
+        }
+        None
+    }
+}
+
+/// A player slot assigned to a connecting gamepad.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum SlotAssignment {
+    /// A new gamepad was assigned a slot.
+    Connected(usize),
+    /// A gamepad reconnected within the grace period
+    /// and got its old slot back.
+    Reconnected(usize),
+}
+
+impl SlotAssignment {
+    /// Returns the player slot.
+    pub fn player(&self) -> usize {
+        match *self {
+            SlotAssignment::Connected(player) => player,
+            SlotAssignment::Reconnected(player) => player,
+        }
+    }
+}
+
+/// Assigns connected gamepads to player slots.
+///
+/// A gamepad that reconnects with the same id within the grace period
+/// gets its old slot back, unless another gamepad took it in the meantime.
+#[derive(Clone, Debug)]
+pub struct PlayerSlots {
+    /// Seconds a slot stays reserved for a disconnected gamepad.
+    pub grace_period: f64,
+    slots: Vec<Option<u32>>,
+    // Slots of disconnected gamepads and seconds since disconnecting.
+    reserved: HashMap<u32, (usize, f64)>,
+}
+
+impl PlayerSlots {
+    /// Creates new player slots for at most `players` gamepads.
+    ///
+    /// Slots stay reserved for disconnected gamepads until reassigned.
+    pub fn new(players: usize) -> PlayerSlots {
+        PlayerSlots {
+            grace_period: ::std::f64::INFINITY,
+            slots: vec![None; players],
+            reserved: HashMap::new(),
+        }
+    }
+
+    /// Returns the player slot of a gamepad.
+    pub fn player(&self, id: u32) -> Option<usize> {
+        self.slots.iter().position(|&slot| slot == Some(id))
+    }
+
+    /// Returns the gamepad in a player slot.
+    pub fn gamepad(&self, player: usize) -> Option<u32> {
+        self.slots.get(player).and_then(|&slot| slot)
+    }
+
+    /// Moves a connected gamepad to a player slot.
+    ///
+    /// A gamepad in that slot swaps to the old slot of the moved gamepad.
+    pub fn set_player(&mut self, id: u32, player: usize) {
+        if player >= self.slots.len() { return; }
+        if let Some(old) = self.player(id) {
+            self.slots[old] = self.slots[player];
+            self.slots[player] = Some(id);
+        }
+    }
+
+    /// Advances time and frees slots whose grace period ran out.
+    pub fn update(&mut self, dt: f64) {
+        let grace_period = self.grace_period;
+        for reserved in self.reserved.values_mut() {
+            reserved.1 += dt;
+        }
+        self.reserved.retain(|_, &mut (_, t)| t <= grace_period);
+    }
+
+    /// Assigns or frees slots when gamepads connect or disconnect.
+    ///
+    /// Returns the slot assigned to a connecting gamepad.
+    pub fn handle_input(&mut self, input: &Input) -> Option<SlotAssignment> {
+        match *input {
+            Input::GamepadConnected(id, true) => {
+                if self.player(id).is_some() { return None; }
+                let assignment = match self.reserved.remove(&id) {
+                    Some((player, _)) if self.slots[player].is_none() =>
+                        Some(SlotAssignment::Reconnected(player)),
+                    _ => {
+                        // Prefer slots not reserved for disconnected gamepads.
+                        let reserved: Vec<usize> = self.reserved.values()
+                            .map(|&(player, _)| player).collect();
+                        (0..self.slots.len())
+                            .find(|&i| self.slots[i].is_none() && !reserved.contains(&i))
+                            .or_else(|| self.slots.iter().position(|slot| slot.is_none()))
+                            .map(SlotAssignment::Connected)
+                    }
+                };
+                if let Some(assignment) = assignment {
+                    let player = assignment.player();
+                    self.reserved.retain(|_, &mut (r, _)| r != player);
+                    self.slots[player] = Some(id);
+                }
+                assignment
+            }
+            Input::GamepadConnected(id, false) => {
+                if let Some(player) = self.player(id) {
+                    self.slots[player] = None;
+                    self.reserved.insert(id, (player, 0.0));
+                }
+                None
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Combines pairs of half-controllers, e.g. Joy-Cons,
+/// into single logical gamepads.
+///
+/// Inputs from both halves of a pair are reported with the id
+/// of the logical gamepad. Unpaired halves are used on their own,
+/// e.g. held sideways by two players.
+#[derive(Clone, Debug)]
+pub struct GamepadPairing {
+    logical: HashMap<u32, u32>,
+}
+
+impl GamepadPairing {
+    /// Creates a new pairing stage without pairs.
+    pub fn new() -> GamepadPairing {
+        GamepadPairing { logical: HashMap::new() }
+    }
+
+    /// Pairs two gamepads into a logical gamepad with the id `id`.
+    pub fn pair(&mut self, left: u32, right: u32, id: u32) {
+        self.unpair(left);
+        self.unpair(right);
+        self.logical.insert(left, id);
+        self.logical.insert(right, id);
+    }
+
+    /// Splits the pair containing a gamepad.
+    pub fn unpair(&mut self, half: u32) {
+        if let Some(id) = self.logical.remove(&half) {
+            self.logical.retain(|_, &mut other| other != id);
+        }
+    }
+
+    /// Returns the id of the logical gamepad containing a gamepad.
+    pub fn logical_id(&self, id: u32) -> u32 {
+        self.logical.get(&id).cloned().unwrap_or(id)
+    }
+
+    /// Rewrites inputs from paired gamepads to their logical gamepad.
+    pub fn handle_input(&self, input: &Input) -> Input {
+        input.map_gamepad_id(|id| self.logical_id(id))
+    }
+}
+
+impl Default for GamepadPairing {
+    fn default() -> GamepadPairing { GamepadPairing::new() }
+}
+
+#[cfg(feature = "quickcheck")]
+impl Arbitrary for GamepadButton {
+    fn arbitrary(g: &mut Gen) -> GamepadButton {
+        if u8::arbitrary(g) % 8 == 0 {
+            GamepadButton::Other(u32::arbitrary(g))
+        } else {
+            *g.choose(BUTTONS).unwrap()
+        }
+    }
+}
+
+#[cfg(feature = "quickcheck")]
+impl Arbitrary for GamepadAxis {
+    fn arbitrary(g: &mut Gen) -> GamepadAxis {
+        if u8::arbitrary(g) % 8 == 0 {
+            GamepadAxis::Other(u32::arbitrary(g))
+        } else {
+            *g.choose(AXES).unwrap()
+        }
+    }
+}
+
+#[cfg(feature = "quickcheck")]
+impl Arbitrary for Battery {
+    fn arbitrary(g: &mut Gen) -> Battery {
+        Battery {
+            percent: Option::<u8>::arbitrary(g).map(|x| x % 101),
+            charging: bool::arbitrary(g),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_labels() {
+        for &vendor in &[Vendor::Xbox, Vendor::PlayStation, Vendor::Nintendo] {
+            for &button in BUTTONS {
+                let label = button.label(vendor);
+                assert_eq!(GamepadButton::from_label(vendor, label), Some(button));
+            }
+        }
+        assert_eq!(GamepadButton::from_label(Vendor::Nintendo, "A"),
+                   Some(GamepadButton::East));
+    }
+
+    #[test]
+    fn test_names() {
+        for &button in BUTTONS {
+            assert_eq!(GamepadButton::from_name(button.name()), Some(button));
+        }
+        for &axis in AXES {
+            assert_eq!(GamepadAxis::from_name(axis.name()), Some(axis));
+        }
+        assert_eq!(GamepadButton::from_name("d-pad up"), Some(GamepadButton::DPadUp));
+        assert_eq!(GamepadAxis::from_name("Nope"), None);
+        assert_eq!(GamepadButton::Other(300).name(), "Other");
+        assert_eq!(GamepadAxis::Other(7).name(), "Other");
+        assert_eq!(GamepadButton::Other(300).label(Vendor::Xbox), "?");
+    }
+
+    #[test]
+    fn test_player_slots() {
+        use self::SlotAssignment::*;
+
+        let mut slots = PlayerSlots::new(2);
+        assert_eq!(slots.handle_input(&Input::GamepadConnected(7, true)), Some(Connected(0)));
+        assert_eq!(slots.handle_input(&Input::GamepadConnected(3, true)), Some(Connected(1)));
+        slots.handle_input(&Input::GamepadConnected(7, false));
+        assert_eq!(slots.gamepad(0), None);
+        assert_eq!(slots.handle_input(&Input::GamepadConnected(7, true)), Some(Reconnected(0)));
+        slots.set_player(3, 0);
+        assert_eq!(slots.player(3), Some(0));
+        assert_eq!(slots.player(7), Some(1));
+
+        slots.grace_period = 1.0;
+        slots.handle_input(&Input::GamepadConnected(7, false));
+        slots.update(2.0);
+        assert_eq!(slots.handle_input(&Input::GamepadConnected(7, true)), Some(Connected(1)));
+    }
+
+    #[test]
+    fn test_trigger_buttons() {
+        let axis = |id, axis, value| Input::Move(Motion::GamepadAxis(id, axis, value));
+        let left = |id| Button::Gamepad(id, GamepadButton::LeftTrigger);
+        let mut triggers = TriggerButtons::new(0.5);
+        assert_eq!(triggers.handle_input(&axis(0, GamepadAxis::LeftTrigger, 0.2)), None);
+        assert_eq!(triggers.handle_input(&axis(0, GamepadAxis::LeftTrigger, 0.6)),
+                   Some(Input::Press(left(0))));
+        assert_eq!(triggers.handle_input(&axis(0, GamepadAxis::LeftTrigger, 0.9)), None);
+        assert_eq!(triggers.handle_input(&axis(1, GamepadAxis::LeftTrigger, 0.9)),
+                   Some(Input::Press(left(1))));
+        assert_eq!(triggers.handle_input(&axis(0, GamepadAxis::LeftStickX, 0.9)), None);
+        assert_eq!(triggers.handle_input(&axis(0, GamepadAxis::LeftTrigger, 0.1)),
+                   Some(Input::Release(left(0))));
+        assert_eq!(triggers.handle_input(&axis(0, GamepadAxis::LeftTrigger, 0.0)), None);
+    }
+
+    #[test]
+    fn test_pairing() {
+        let press = |id| Input::Press(Button::Gamepad(id, GamepadButton::South));
+        let mut pairing = GamepadPairing::new();
+        pairing.pair(1, 2, 10);
+        assert_eq!(pairing.handle_input(&press(1)), press(10));
+        assert_eq!(pairing.handle_input(&press(2)), press(10));
+        assert_eq!(pairing.handle_input(&press(3)), press(3));
+        assert_eq!(pairing.handle_input(&Input::GamepadConnected(2, true)),
+                   Input::GamepadConnected(10, true));
+
+        pairing.pair(2, 3, 11);
+        assert_eq!(pairing.logical_id(1), 1);
+        assert_eq!(pairing.logical_id(2), 11);
+        assert_eq!(pairing.logical_id(3), 11);
+        pairing.unpair(3);
+        assert_eq!(pairing.logical_id(2), 2);
+        assert_eq!(pairing.logical_id(3), 3);
+    }
+}
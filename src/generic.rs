@@ -0,0 +1,222 @@
+//! Closure based extraction of input from any event type.
+//!
+//! Widget libraries can be generic over `GenericEvent`
+//! instead of converting each back-end's event type.
+
+use { Button, Input, InputRef, Key, Motion, MouseButton };
+
+/// Implemented by event types carrying input.
+pub trait GenericEvent {
+    /// Returns the input of the event, if any.
+    fn input_ref<'a>(&'a self) -> Option<InputRef<'a>>;
+
+    /// Calls closure if a button was pressed.
+    fn if_press<U, F>(&self, f: F) -> Option<U>
+        where F: FnOnce(Button) -> U
+    {
+        match self.input_ref() {
+            Some(InputRef::Press(button)) => Some(f(button)),
+            _ => None,
+        }
+    }
+
+    /// Calls closure if a button was released.
+    fn if_release<U, F>(&self, f: F) -> Option<U>
+        where F: FnOnce(Button) -> U
+    {
+        match self.input_ref() {
+            Some(InputRef::Release(button)) => Some(f(button)),
+            _ => None,
+        }
+    }
+
+    /// Calls closure if a key was pressed.
+    fn if_key_press<U, F>(&self, f: F) -> Option<U>
+        where F: FnOnce(Key) -> U
+    {
+        match self.input_ref() {
+            Some(InputRef::Press(Button::Keyboard(key))) => Some(f(key)),
+            _ => None,
+        }
+    }
+
+    /// Calls closure if a key was released.
+    fn if_key_release<U, F>(&self, f: F) -> Option<U>
+        where F: FnOnce(Key) -> U
+    {
+        match self.input_ref() {
+            Some(InputRef::Release(Button::Keyboard(key))) => Some(f(key)),
+            _ => None,
+        }
+    }
+
+    /// Calls closure if a mouse button was pressed.
+    fn if_mouse_press<U, F>(&self, f: F) -> Option<U>
+        where F: FnOnce(MouseButton) -> U
+    {
+        match self.input_ref() {
+            Some(InputRef::Press(Button::Mouse(button))) => Some(f(button)),
+            _ => None,
+        }
+    }
+
+    /// Calls closure if a mouse button was released.
+    fn if_mouse_release<U, F>(&self, f: F) -> Option<U>
+        where F: FnOnce(MouseButton) -> U
+    {
+        match self.input_ref() {
+            Some(InputRef::Release(Button::Mouse(button))) => Some(f(button)),
+            _ => None,
+        }
+    }
+
+    /// Calls closure if the mouse cursor moved, with x and y in window coordinates.
+    fn if_mouse_move<U, F>(&self, f: F) -> Option<U>
+        where F: FnOnce(f64, f64) -> U
+    {
+        match self.input_ref() {
+            Some(InputRef::Move(Motion::MouseCursor(x, y))) => Some(f(x, y)),
+            _ => None,
+        }
+    }
+
+    /// Calls closure if the mouse moved relatively.
+    fn if_mouse_relative<U, F>(&self, f: F) -> Option<U>
+        where F: FnOnce(f64, f64) -> U
+    {
+        match self.input_ref() {
+            Some(InputRef::Move(Motion::MouseRelative(x, y))) => Some(f(x, y)),
+            _ => None,
+        }
+    }
+
+    /// Calls closure if the mouse scrolled.
+    fn if_mouse_scroll<U, F>(&self, f: F) -> Option<U>
+        where F: FnOnce(f64, f64) -> U
+    {
+        match self.input_ref() {
+            Some(InputRef::Move(Motion::MouseScroll(x, y))) => Some(f(x, y)),
+            _ => None,
+        }
+    }
+
+    /// Calls closure if text was entered.
+    fn if_text<U, F>(&self, f: F) -> Option<U>
+        where F: FnOnce(&str) -> U
+    {
+        match self.input_ref() {
+            Some(InputRef::Text(text)) => Some(f(text)),
+            _ => None,
+        }
+    }
+
+    /// Calls closure if the window was resized.
+    fn if_resize<U, F>(&self, f: F) -> Option<U>
+        where F: FnOnce(u32, u32) -> U
+    {
+        match self.input_ref() {
+            Some(InputRef::Resize(w, h)) => Some(f(w, h)),
+            _ => None,
+        }
+    }
+
+    /// Calls closure if the window gained or lost focus.
+    fn if_focus<U, F>(&self, f: F) -> Option<U>
+        where F: FnOnce(bool) -> U
+    {
+        match self.input_ref() {
+            Some(InputRef::Focus(focus)) => Some(f(focus)),
+            _ => None,
+        }
+    }
+}
+
+/// Implements `GenericEvent` for a back-end event type
+/// from a list of patterns and the inputs they map to.
+///
+/// Events matching none of the patterns carry no input.
+///
+/// ```
+/// #[macro_use]
+/// extern crate input;
+///
+/// use input::{ Button, InputRef, Key };
+///
+/// enum BackendEvent {
+///     KeyDown(Key),
+///     Char(String),
+///     Redraw,
+/// }
+///
+/// generic_event! {
+///     BackendEvent {
+///         BackendEvent::KeyDown(key) => InputRef::Press(Button::Keyboard(key)),
+///         BackendEvent::Char(ref text) => InputRef::Text(text),
+///     }
+/// }
+/// # fn main() {}
+/// ```
+#[macro_export]
+macro_rules! generic_event {
+    ($ty:ty { $($pat:pat => $input:expr,)* }) => {
+        impl $crate::generic::GenericEvent for $ty {
+            fn input_ref<'a>(&'a self) -> Option<$crate::InputRef<'a>> {
+                #[allow(unreachable_patterns)]
+                match *self {
+                    $($pat => Some($input),)*
+                    _ => None,
+                }
+            }
+        }
+    }
+}
+
+impl GenericEvent for Input {
+    fn input_ref<'a>(&'a self) -> Option<InputRef<'a>> {
+        Some(self.to_ref())
+    }
+}
+
+impl<'a> GenericEvent for InputRef<'a> {
+    fn input_ref<'b>(&'b self) -> Option<InputRef<'b>> {
+        Some(*self)
+    }
+}
+
+impl<T: GenericEvent> GenericEvent for Option<T> {
+    fn input_ref<'a>(&'a self) -> Option<InputRef<'a>> {
+        self.as_ref().and_then(|event| event.input_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use { Button, Input, InputRef, Key };
+
+    #[test]
+    fn test_generic_event() {
+        let press = Input::key_press(Key::A);
+        assert_eq!(press.if_key_press(|key| key), Some(Key::A));
+        assert_eq!(press.if_mouse_move(|x, y| x + y), None);
+        assert_eq!(Input::move_to(1.0, 2.0).to_ref().if_mouse_move(|x, y| x + y), Some(3.0));
+        assert_eq!(Some(Input::Text("a".to_string())).if_text(|text| text.len()), Some(1));
+    }
+
+    enum BackendEvent {
+        KeyDown(Key),
+        Redraw,
+    }
+
+    generic_event! {
+        BackendEvent {
+            BackendEvent::KeyDown(key) => InputRef::Press(Button::Keyboard(key)),
+        }
+    }
+
+    #[test]
+    fn test_generic_event_macro() {
+        assert_eq!(BackendEvent::KeyDown(Key::A).if_key_press(|key| key), Some(Key::A));
+        assert_eq!(BackendEvent::Redraw.input_ref(), None);
+    }
+}
@@ -0,0 +1,107 @@
+//! Comparison of input handling against golden files.
+//!
+//! Inputs are replayed through a handler, and the `Debug` formatting
+//! of everything it outputs is compared line by line with a golden file,
+//! guarding input processing against regressions.
+
+use std::fmt;
+
+use Input;
+
+/// The first line where output differs from the golden file.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Divergence {
+    /// The index of the line, starting at 0.
+    pub line: usize,
+    /// The line in the golden file, if any.
+    pub expected: Option<String>,
+    /// The line of output, if any.
+    pub actual: Option<String>,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: expected {}, got {}", self.line + 1,
+            self.expected.as_ref().map(|s| &s[..]).unwrap_or("end of file"),
+            self.actual.as_ref().map(|s| &s[..]).unwrap_or("end of output"))
+    }
+}
+
+/// Replays inputs through a handler, returning the golden file contents.
+pub fn record<'a, I, F, T>(inputs: I, handler: F) -> String
+    where I: IntoIterator<Item = &'a Input>,
+          F: FnMut(&Input) -> Vec<T>,
+          T: fmt::Debug
+{
+    let mut golden = String::new();
+    for line in output(inputs, handler) {
+        golden.push_str(&line);
+        golden.push('\n');
+    }
+    golden
+}
+
+/// Replays inputs through a handler and compares output with a golden file.
+pub fn compare<'a, I, F, T>(inputs: I, handler: F, golden: &str)
+    -> Result<(), Divergence>
+    where I: IntoIterator<Item = &'a Input>,
+          F: FnMut(&Input) -> Vec<T>,
+          T: fmt::Debug
+{
+    let mut expected = golden.lines();
+    let mut actual = output(inputs, handler).into_iter();
+    let mut line = 0;
+    loop {
+        match (expected.next(), actual.next()) {
+            (None, None) => return Ok(()),
+            (Some(a), Some(ref b)) if a == b => {}
+            (a, b) => return Err(Divergence {
+                line: line,
+                expected: a.map(|s| s.to_string()),
+                actual: b,
+            }),
+        }
+        line += 1;
+    }
+}
+
+fn output<'a, I, F, T>(inputs: I, mut handler: F) -> Vec<String>
+    where I: IntoIterator<Item = &'a Input>,
+          F: FnMut(&Input) -> Vec<T>,
+          T: fmt::Debug
+{
+    let mut lines = vec![];
+    for input in inputs {
+        for x in handler(input) {
+            lines.push(format!("{:?}", x));
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use { Button, Input, Key };
+
+    #[test]
+    fn test_compare() {
+        let inputs = vec![
+            Input::Press(Button::Keyboard(Key::A)),
+            Input::Release(Button::Keyboard(Key::A)),
+        ];
+        let handler = |input: &Input| match *input {
+            Input::Press(_) => vec!["jump"],
+            _ => vec![],
+        };
+        let golden = record(&inputs, handler);
+        assert_eq!(golden, "\"jump\"\n");
+        assert_eq!(compare(&inputs, handler, &golden), Ok(()));
+        assert_eq!(compare(&inputs, handler, "\"jump\"\n\"jump\"\n"),
+                   Err(Divergence {
+                       line: 1,
+                       expected: Some("\"jump\"".to_string()),
+                       actual: None,
+                   }));
+    }
+}
@@ -0,0 +1,133 @@
+//! Heatmaps of clicks and touches.
+
+use { Button, Input, Motion };
+use touch::TouchPhase;
+
+/// Counts mouse presses and touches in a grid of bins over the window,
+/// e.g. for UX research or debug overlays.
+///
+/// Resizing the window clears the counts, since the bins then cover
+/// different parts of the content.
+#[derive(Clone, Debug)]
+pub struct Heatmap {
+    resolution: [usize; 2],
+    size: [f64; 2],
+    cursor: Option<[f64; 2]>,
+    // Counts in row-major order.
+    bins: Vec<u32>,
+}
+
+impl Heatmap {
+    /// Creates a new heatmap with a number of columns and rows
+    /// over a window size.
+    ///
+    /// The resolution is at least one column and row.
+    pub fn new(resolution: [usize; 2], size: [u32; 2]) -> Heatmap {
+        let resolution = [resolution[0].max(1), resolution[1].max(1)];
+        Heatmap {
+            resolution: resolution,
+            size: [size[0] as f64, size[1] as f64],
+            cursor: None,
+            bins: vec![0; resolution[0] * resolution[1]],
+        }
+    }
+
+    /// Returns the number of columns and rows.
+    pub fn resolution(&self) -> [usize; 2] {
+        self.resolution
+    }
+
+    /// Returns the count of a bin.
+    pub fn count(&self, column: usize, row: usize) -> u32 {
+        if column >= self.resolution[0] || row >= self.resolution[1] { return 0; }
+        self.bins[row * self.resolution[0] + column]
+    }
+
+    /// Returns the counts of all bins in row-major order.
+    pub fn bins(&self) -> &[u32] {
+        &self.bins
+    }
+
+    /// Returns the highest count, e.g. for normalizing colors.
+    pub fn max(&self) -> u32 {
+        self.bins.iter().cloned().max().unwrap_or(0)
+    }
+
+    /// Clears all counts.
+    pub fn clear(&mut self) {
+        for bin in &mut self.bins { *bin = 0; }
+    }
+
+    /// Counts mouse presses and touches that begin.
+    pub fn handle_input(&mut self, input: &Input) {
+        match *input {
+            Input::Move(Motion::MouseCursor(x, y)) => self.cursor = Some([x, y]),
+            Input::Press(Button::Mouse(_)) => {
+                if let Some(pos) = self.cursor { self.add(pos); }
+            }
+            Input::Touch(touch) if touch.phase == TouchPhase::Began => self.add(touch.position),
+            Input::Resize(w, h) => {
+                let size = [w as f64, h as f64];
+                if size != self.size {
+                    self.size = size;
+                    self.clear();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Exports the counts as CSV, one line per row.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::new();
+        for row in self.bins.chunks(self.resolution[0]) {
+            let row: Vec<String> = row.iter().map(|count| count.to_string()).collect();
+            csv.push_str(&row.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+
+    fn add(&mut self, pos: [f64; 2]) {
+        if !(pos[0] >= 0.0 && pos[0] < self.size[0] && pos[1] >= 0.0 && pos[1] < self.size[1]) {
+            return;
+        }
+        let column = (pos[0] / self.size[0] * self.resolution[0] as f64) as usize;
+        let row = (pos[1] / self.size[1] * self.resolution[1] as f64) as usize;
+        self.bins[row * self.resolution[0] + column] += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use { Input, MouseButton, Touch };
+    use touch::TouchPhase;
+
+    #[test]
+    fn test_heatmap() {
+        let mut heatmap = Heatmap::new([2, 2], [100, 100]);
+        heatmap.handle_input(&Input::mouse_press(MouseButton::Left));
+        assert_eq!(heatmap.max(), 0);
+        heatmap.handle_input(&Input::move_to(75.0, 25.0));
+        heatmap.handle_input(&Input::mouse_press(MouseButton::Left));
+        heatmap.handle_input(&Input::Touch(Touch::new(0, TouchPhase::Began, [75.0, 10.0])));
+        heatmap.handle_input(&Input::Touch(Touch::new(0, TouchPhase::Moved, [10.0, 75.0])));
+        heatmap.handle_input(&Input::Touch(Touch::new(1, TouchPhase::Began, [10.0, 75.0])));
+        assert_eq!(heatmap.bins(), &[0, 2, 1, 0]);
+        assert_eq!(heatmap.to_csv(), "0,2\n1,0\n");
+
+        heatmap.handle_input(&Input::Resize(100, 100));
+        assert_eq!(heatmap.max(), 2);
+        heatmap.handle_input(&Input::Resize(200, 100));
+        assert_eq!(heatmap.max(), 0);
+    }
+
+    #[test]
+    fn test_zero_resolution() {
+        let mut heatmap = Heatmap::new([0, 3], [100, 100]);
+        assert_eq!(heatmap.resolution(), [1, 3]);
+        heatmap.handle_input(&Input::Touch(Touch::new(0, TouchPhase::Began, [50.0, 50.0])));
+        assert_eq!(heatmap.count(0, 1), 1);
+    }
+}
@@ -0,0 +1,116 @@
+//! Detection of idle users.
+
+use Input;
+
+struct Threshold {
+    seconds: f64,
+    fired: bool,
+    callback: Box<dyn FnMut(f64)>,
+}
+
+/// Tracks time since the last user input.
+pub struct IdleTracker {
+    /// When set, only inputs accepted by the filter count as activity.
+    pub filter: Option<fn(&Input) -> bool>,
+    idle: f64,
+    thresholds: Vec<Threshold>,
+}
+
+impl IdleTracker {
+    /// Creates a new idle tracker.
+    pub fn new() -> IdleTracker {
+        IdleTracker {
+            filter: None,
+            idle: 0.0,
+            thresholds: vec![],
+        }
+    }
+
+    /// Calls `callback` with the idle time once the user has been idle
+    /// for `seconds`.
+    ///
+    /// The callback is called again after the next idle period.
+    pub fn on_idle<F>(&mut self, seconds: f64, callback: F)
+        where F: FnMut(f64) + 'static
+    {
+        self.thresholds.push(Threshold {
+            seconds: seconds,
+            fired: false,
+            callback: Box::new(callback),
+        });
+    }
+
+    /// Returns seconds since the last user input.
+    pub fn idle_time(&self) -> f64 {
+        self.idle
+    }
+
+    /// Registers user activity unless the input is filtered out.
+    pub fn handle_input(&mut self, input: &Input) {
+        if let Some(filter) = self.filter {
+            if !filter(input) { return; }
+        }
+        self.idle = 0.0;
+        for threshold in &mut self.thresholds {
+            threshold.fired = false;
+        }
+    }
+
+    /// Advances time and calls the callbacks of passed thresholds.
+    pub fn update(&mut self, dt: f64) {
+        self.idle += dt;
+        let idle = self.idle;
+        for threshold in &mut self.thresholds {
+            if !threshold.fired && idle >= threshold.seconds {
+                threshold.fired = true;
+                (threshold.callback)(idle);
+            }
+        }
+    }
+}
+
+impl Default for IdleTracker {
+    fn default() -> IdleTracker { IdleTracker::new() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use { Button, Input, Key, Motion };
+
+    #[test]
+    fn test_threshold() {
+        let calls = Rc::new(RefCell::new(vec![]));
+        let mut idle = IdleTracker::new();
+        let idle_calls = calls.clone();
+        idle.on_idle(1.0, move |time| idle_calls.borrow_mut().push(time));
+        idle.update(0.5);
+        assert!(calls.borrow().is_empty());
+        idle.update(0.5);
+        idle.update(0.5);
+        assert_eq!(*calls.borrow(), vec![1.0]);
+        assert_eq!(idle.idle_time(), 1.5);
+    }
+
+    #[test]
+    fn test_reset() {
+        let calls = Rc::new(RefCell::new(0));
+        let mut idle = IdleTracker::new();
+        let idle_calls = calls.clone();
+        idle.on_idle(1.0, move |_| *idle_calls.borrow_mut() += 1);
+        idle.update(2.0);
+        idle.handle_input(&Input::Press(Button::Keyboard(Key::A)));
+        assert_eq!(idle.idle_time(), 0.0);
+        idle.update(0.5);
+        assert_eq!(*calls.borrow(), 1);
+        idle.update(0.5);
+        assert_eq!(*calls.borrow(), 2);
+
+        fn is_press(input: &Input) -> bool { input.as_press().is_some() }
+        idle.filter = Some(is_press);
+        idle.handle_input(&Input::Move(Motion::MouseRelative(1.0, 0.0)));
+        assert_eq!(idle.idle_time(), 1.0);
+    }
+}
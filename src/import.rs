@@ -0,0 +1,112 @@
+//! Import of bindings from other formats.
+//!
+//! INI-style keymap files bind one action per line:
+//!
+//! ```ini
+//! ; Comments start with ';' or '#'.
+//! [Keys]
+//! Jump = Space, gamepad_south
+//! Fire = mouse_left
+//! ```
+//!
+//! Buttons are prompt names, e.g. `kbd_space`, or key names like `Space`.
+//! Section headers are ignored.
+
+use std::error::Error;
+use std::fmt;
+use std::hash::Hash;
+
+use Button;
+use action::ActionMap;
+
+/// An error on a line of an imported file.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ImportError {
+    /// The line number, starting at 1.
+    pub line: usize,
+    /// What went wrong.
+    pub message: String,
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for ImportError {}
+
+/// Parses an INI-style keymap into action names and buttons.
+pub fn parse_ini(text: &str) -> Result<Vec<(String, Button)>, ImportError> {
+    parse(text).map(|bindings| bindings.into_iter()
+        .map(|(_, action, button)| (action, button)).collect())
+}
+
+/// Binds the buttons of an INI-style keymap in the base layer.
+///
+/// Action names are converted to actions with `action`.
+/// Unknown action names are an error, and nothing is bound.
+pub fn import_ini<A, F>(actions: &mut ActionMap<A>, text: &str, mut action: F)
+    -> Result<(), ImportError>
+    where A: Copy + Eq + Hash, F: FnMut(&str) -> Option<A>
+{
+    let mut resolved = vec![];
+    for (line, name, button) in parse(text)? {
+        match action(&name) {
+            Some(a) => resolved.push((button, a)),
+            None => return Err(ImportError {
+                line: line,
+                message: format!("unknown action `{}`", name),
+            }),
+        }
+    }
+    for (button, a) in resolved {
+        actions.bind(button, a);
+    }
+    Ok(())
+}
+
+// Returns bindings with their line numbers.
+fn parse(text: &str) -> Result<Vec<(usize, String, Button)>, ImportError> {
+    let mut bindings = vec![];
+    for (i, line) in text.lines().enumerate() {
+        let error = |message: String| ImportError { line: i + 1, message: message };
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(';') || line.starts_with('#') ||
+           line.starts_with('[') {
+            continue;
+        }
+        let eq = match line.find('=') {
+            Some(eq) => eq,
+            None => return Err(error("expected `action = button`".to_string())),
+        };
+        let action = line[..eq].trim();
+        for name in line[eq + 1..].split(',') {
+            let name = name.trim();
+            if name.is_empty() { continue; }
+            match name.parse::<Button>() {
+                Ok(button) => bindings.push((i + 1, action.to_string(), button)),
+                Err(err) => return Err(error(err.to_string())),
+            }
+        }
+    }
+    Ok(bindings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use { Button, GamepadButton };
+    use keyboard::Key;
+
+    #[test]
+    fn test_parse_ini() {
+        let bindings = parse_ini("[Keys]\nJump = Space, gamepad_south\n; Fire\n").unwrap();
+        assert_eq!(bindings, vec![
+            ("Jump".to_string(), Button::Keyboard(Key::Space)),
+            ("Jump".to_string(), Button::Gamepad(0, GamepadButton::South)),
+        ]);
+        assert_eq!(parse_ini("Jump\n").unwrap_err().line, 1);
+        assert_eq!(parse_ini("\nJump = Nope").unwrap_err().line, 2);
+    }
+}
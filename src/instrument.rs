@@ -0,0 +1,144 @@
+//! Rhythm game instrument controllers.
+//!
+//! Instruments are reported as gamepads. Their buttons map to the
+//! standard gamepad layout, so they work with bindings like any gamepad.
+
+use { GamepadAxis, GamepadButton };
+
+#[cfg(feature = "quickcheck")]
+use quickcheck::{ Arbitrary, Gen };
+
+/// The axis reporting the whammy bar of a guitar, from 0 to 1.
+pub const WHAMMY: GamepadAxis = GamepadAxis::RightTrigger;
+
+/// Represent a guitar controller button.
+#[derive(Copy, Clone, RustcDecodable, RustcEncodable, PartialEq,
+    Eq, Ord, PartialOrd, Hash, Debug)]
+pub enum GuitarButton {
+    /// The green fret.
+    Green,
+    /// The red fret.
+    Red,
+    /// The yellow fret.
+    Yellow,
+    /// The blue fret.
+    Blue,
+    /// The orange fret.
+    Orange,
+    /// Strumming up.
+    StrumUp,
+    /// Strumming down.
+    StrumDown,
+    /// The star power button.
+    StarPower,
+    /// The start button.
+    Start,
+}
+
+impl GuitarButton {
+    /// Returns the standard gamepad button reporting the guitar button.
+    pub fn to_gamepad(&self) -> GamepadButton {
+        match *self {
+            GuitarButton::Green => GamepadButton::South,
+            GuitarButton::Red => GamepadButton::East,
+            GuitarButton::Yellow => GamepadButton::North,
+            GuitarButton::Blue => GamepadButton::West,
+            GuitarButton::Orange => GamepadButton::LeftShoulder,
+            GuitarButton::StrumUp => GamepadButton::DPadUp,
+            GuitarButton::StrumDown => GamepadButton::DPadDown,
+            GuitarButton::StarPower => GamepadButton::Select,
+            GuitarButton::Start => GamepadButton::Start,
+        }
+    }
+
+    /// Returns the guitar button reported as a standard gamepad button.
+    pub fn from_gamepad(button: GamepadButton) -> Option<GuitarButton> {
+        match button {
+            GamepadButton::South => Some(GuitarButton::Green),
+            GamepadButton::East => Some(GuitarButton::Red),
+            GamepadButton::North => Some(GuitarButton::Yellow),
+            GamepadButton::West => Some(GuitarButton::Blue),
+            GamepadButton::LeftShoulder => Some(GuitarButton::Orange),
+            GamepadButton::DPadUp => Some(GuitarButton::StrumUp),
+            GamepadButton::DPadDown => Some(GuitarButton::StrumDown),
+            GamepadButton::Select => Some(GuitarButton::StarPower),
+            GamepadButton::Start => Some(GuitarButton::Start),
+            _ => None,
+        }
+    }
+}
+
+/// Represent a drum controller pad.
+#[derive(Copy, Clone, RustcDecodable, RustcEncodable, PartialEq,
+    Eq, Ord, PartialOrd, Hash, Debug)]
+pub enum DrumPad {
+    /// The red pad.
+    Red,
+    /// The yellow pad or cymbal.
+    Yellow,
+    /// The blue pad or cymbal.
+    Blue,
+    /// The green pad or cymbal.
+    Green,
+    /// The kick pedal.
+    Kick,
+}
+
+impl DrumPad {
+    /// Returns the standard gamepad button reporting the pad.
+    pub fn to_gamepad(&self) -> GamepadButton {
+        match *self {
+            DrumPad::Red => GamepadButton::East,
+            DrumPad::Yellow => GamepadButton::North,
+            DrumPad::Blue => GamepadButton::West,
+            DrumPad::Green => GamepadButton::South,
+            DrumPad::Kick => GamepadButton::LeftShoulder,
+        }
+    }
+
+    /// Returns the pad reported as a standard gamepad button.
+    pub fn from_gamepad(button: GamepadButton) -> Option<DrumPad> {
+        match button {
+            GamepadButton::East => Some(DrumPad::Red),
+            GamepadButton::North => Some(DrumPad::Yellow),
+            GamepadButton::West => Some(DrumPad::Blue),
+            GamepadButton::South => Some(DrumPad::Green),
+            GamepadButton::LeftShoulder => Some(DrumPad::Kick),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "quickcheck")]
+impl Arbitrary for DrumPad {
+    fn arbitrary(g: &mut Gen) -> DrumPad {
+        *g.choose(&[DrumPad::Red, DrumPad::Yellow, DrumPad::Blue,
+                    DrumPad::Green, DrumPad::Kick]).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_guitar() {
+        use self::GuitarButton::*;
+
+        let buttons = [Green, Red, Yellow, Blue, Orange, StrumUp, StrumDown, StarPower, Start];
+        for &button in &buttons {
+            assert_eq!(GuitarButton::from_gamepad(button.to_gamepad()), Some(button));
+        }
+        assert_eq!(GuitarButton::from_gamepad(GamepadButton::Guide), None);
+    }
+
+    #[test]
+    fn test_drums() {
+        use self::DrumPad::*;
+
+        for &pad in &[Red, Yellow, Blue, Green, Kick] {
+            assert_eq!(DrumPad::from_gamepad(pad.to_gamepad()), Some(pad));
+        }
+        assert_eq!(DrumPad::from_gamepad(GamepadButton::Start), None);
+    }
+}
@@ -4,10 +4,16 @@ use num::FromPrimitive;
 use num::ToPrimitive;
 use std::default::Default;
 use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
 
 use Input;
 use Button;
 
+#[cfg(feature = "quickcheck")]
+use quickcheck::{ Arbitrary, Gen };
+
 // Defining every combination to allow assignment in static expressions.
 bitflags!(
     #[allow(missing_docs)]
@@ -23,6 +29,8 @@ bitflags!(
         const ALT                   = 0b00000100,
         /// GUI
         const GUI                   = 0b00001000,
+        /// ALT_GR (ISO level 3 shift)
+        const ALT_GR                = 0b00010000,
         /// CTRL_SHIFT
         const CTRL_SHIFT            = CTRL.bits
                                     | SHIFT.bits,
@@ -83,20 +91,95 @@ impl ModifierKey {
           | Input::Press(Button::Keyboard(Key::RGui)) => self.insert(GUI),
             Input::Release(Button::Keyboard(Key::LGui))
           | Input::Release(Button::Keyboard(Key::RGui)) => self.remove(GUI),
+            Input::Press(Button::Keyboard(Key::Mode)) => self.insert(ALT_GR),
+            Input::Release(Button::Keyboard(Key::Mode)) => self.remove(ALT_GR),
             Input::Focus(false) => *self = NO_MODIFIER,
             _ => {}
         }
     }
+
+    /// Returns `true` if AltGr is held.
+    ///
+    /// Back-ends report AltGr as `Key::Mode`, except on Windows
+    /// where it is reported as Ctrl+Alt. Set `ctrl_alt` for such back-ends,
+    /// e.g. to `cfg!(windows)`, to count Ctrl+Alt as AltGr.
+    pub fn is_alt_gr(&self, ctrl_alt: bool) -> bool {
+        self.contains(ALT_GR) || (ctrl_alt && self.contains(CTRL_ALT))
+    }
 }
 
 impl Default for ModifierKey {
     fn default() -> ModifierKey { NO_MODIFIER }
 }
 
-/// Represent a keyboard key.
-#[allow(missing_docs)]
-#[derive(Copy, Clone, RustcDecodable, RustcEncodable, Debug, Hash)]
-pub enum Key {
+/// Exclusive keyboard access, implemented by back-ends.
+///
+/// While grabbed, the back-end suppresses operating system shortcuts
+/// where the platform allows it, e.g. for kiosks and full-screen games.
+pub trait KeyboardCapture {
+    /// Requests exclusive keyboard access.
+    ///
+    /// Returns `true` if the keyboard was grabbed.
+    fn grab(&mut self) -> bool;
+    /// Releases exclusive keyboard access.
+    fn ungrab(&mut self);
+    /// Returns `true` if the keyboard is grabbed.
+    fn is_grabbed(&self) -> bool;
+}
+
+/// The number of simultaneous key presses a keyboard can report.
+#[derive(Copy, Clone, RustcDecodable, RustcEncodable, PartialEq, Eq, Hash, Debug)]
+pub enum Rollover {
+    /// Any number of keys (N-key rollover).
+    NKey,
+    /// At most this many keys, e.g. 6 for USB boot protocol keyboards.
+    Limited(u32),
+    /// The rollover is not known.
+    Unknown,
+}
+
+/// Information about a keyboard, implemented by back-ends.
+pub trait KeyboardDevice {
+    /// Returns the number of simultaneous key presses the keyboard reports.
+    ///
+    /// Keyboards with limited rollover may drop presses or report
+    /// ghost keys when many keys are held.
+    fn rollover(&self) -> Rollover;
+}
+
+/// Requests for the on-screen keyboard, implemented by back-ends.
+///
+/// On touch-only platforms, the back-end shows or hides the software
+/// keyboard and reports it with `Input::OnScreenKeyboard`.
+pub trait OnScreenKeyboard {
+    /// Requests text input for a widget, showing the on-screen keyboard.
+    ///
+    /// The rectangle `[x, y, w, h]` in window coordinates is the area of
+    /// the widget, which the back-end should keep visible.
+    fn request_text_input(&mut self, rect: [f64; 4]);
+    /// Ends text input, hiding the on-screen keyboard.
+    fn dismiss_text_input(&mut self);
+}
+
+// Defines `Key` together with a table of all keys,
+// so the table can not drift from the enum.
+macro_rules! keys {
+    ($($name:ident = $code:expr,)*) => {
+        /// Represent a keyboard key.
+        #[allow(missing_docs)]
+        #[derive(Copy, Clone, RustcDecodable, RustcEncodable, Debug, Hash)]
+        pub enum Key {
+            $($name = $code,)*
+        }
+
+        // All keys sorted by code.
+        pub(crate) static KEYS: &'static [Key] = &[$(Key::$name,)*];
+        // Names of all keys in the order of `KEYS`.
+        pub(crate) static KEY_NAMES: &'static [&'static str] = &[$(stringify!($name),)*];
+    }
+}
+
+keys! {
     Unknown                 = 0,
     Backspace               = 8,
     Tab                     = 9,
@@ -336,6 +419,73 @@ pub enum Key {
 }
 
 
+// Defines `ConsumerKey` with conversions from and to `Key`
+// for keys of the same name.
+macro_rules! consumer_keys {
+    ($($name:ident,)*) => {
+        /// Represent a media or system key.
+        ///
+        /// These keys are also reported as `Key`, and can be told apart
+        /// with `Key::to_consumer` to keep them out of game bindings.
+        #[allow(missing_docs)]
+        #[derive(Copy, Clone, RustcDecodable, RustcEncodable, PartialEq,
+            Eq, Hash, Debug)]
+        pub enum ConsumerKey {
+            $($name,)*
+        }
+
+        impl Key {
+            /// Returns the consumer key, if this is a media or system key.
+            pub fn to_consumer(&self) -> Option<ConsumerKey> {
+                match *self {
+                    $(Key::$name => Some(ConsumerKey::$name),)*
+                    _ => None,
+                }
+            }
+        }
+
+        impl From<ConsumerKey> for Key {
+            fn from(key: ConsumerKey) -> Key {
+                match key {
+                    $(ConsumerKey::$name => Key::$name,)*
+                }
+            }
+        }
+    }
+}
+
+consumer_keys! {
+    Mute,
+    VolumeUp,
+    VolumeDown,
+    Power,
+    AudioNext,
+    AudioPrev,
+    AudioStop,
+    AudioPlay,
+    AudioMute,
+    MediaSelect,
+    Www,
+    Mail,
+    Calculator,
+    Computer,
+    AcSearch,
+    AcHome,
+    AcBack,
+    AcForward,
+    AcStop,
+    AcRefresh,
+    AcBookmarks,
+    BrightnessDown,
+    BrightnessUp,
+    DisplaySwitch,
+    KbdIllumToggle,
+    KbdIllumDown,
+    KbdIllumUp,
+    Eject,
+    Sleep,
+}
+
 impl PartialEq for Key {
     fn eq(&self, other: &Key) -> bool {
         return (*self as i32) == (*other as i32);
@@ -364,6 +514,72 @@ impl Key {
     pub fn code(&self) -> i32 {
         *self as i32
     }
+
+    /// Resolves a numpad key depending on the num lock state.
+    ///
+    /// With num lock on, numpad digits resolve to digits and the numpad
+    /// period to `Period`. With num lock off, they resolve to navigation
+    /// keys, e.g. `NumPad8` to `Up`. Numpad operators always resolve to
+    /// their main keyboard counterparts. Other keys are unchanged.
+    pub fn resolve_numpad(&self, num_lock: bool) -> Key {
+        match (*self, num_lock) {
+            (Key::NumPad0, true) => Key::D0,
+            (Key::NumPad1, true) => Key::D1,
+            (Key::NumPad2, true) => Key::D2,
+            (Key::NumPad3, true) => Key::D3,
+            (Key::NumPad4, true) => Key::D4,
+            (Key::NumPad5, true) => Key::D5,
+            (Key::NumPad6, true) => Key::D6,
+            (Key::NumPad7, true) => Key::D7,
+            (Key::NumPad8, true) => Key::D8,
+            (Key::NumPad9, true) => Key::D9,
+            (Key::NumPadPeriod, true) => Key::Period,
+            (Key::NumPad0, false) => Key::Insert,
+            (Key::NumPad1, false) => Key::End,
+            (Key::NumPad2, false) => Key::Down,
+            (Key::NumPad3, false) => Key::PageDown,
+            (Key::NumPad4, false) => Key::Left,
+            (Key::NumPad6, false) => Key::Right,
+            (Key::NumPad7, false) => Key::Home,
+            (Key::NumPad8, false) => Key::Up,
+            (Key::NumPad9, false) => Key::PageUp,
+            (Key::NumPadPeriod, false) => Key::Delete,
+            (Key::NumPadDivide, _) => Key::Slash,
+            (Key::NumPadMultiply, _) => Key::Asterisk,
+            (Key::NumPadMinus, _) => Key::Minus,
+            (Key::NumPadPlus, _) => Key::Plus,
+            (Key::NumPadEnter, _) => Key::Return,
+            (Key::NumPadEquals, _) => Key::Equals,
+            (key, _) => key,
+        }
+    }
+}
+
+/// An error parsing a key name.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ParseKeyError {
+    /// The name that is not a key.
+    pub name: String,
+}
+
+impl fmt::Display for ParseKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unknown key `{}`", self.name)
+    }
+}
+
+impl Error for ParseKeyError {}
+
+/// Parses a key from its name, e.g. `"Space"`, ignoring case.
+impl FromStr for Key {
+    type Err = ParseKeyError;
+
+    fn from_str(name: &str) -> Result<Key, ParseKeyError> {
+        KEYS.iter().zip(KEY_NAMES)
+            .find(|&(_, key_name)| key_name.eq_ignore_ascii_case(name))
+            .map(|(&key, _)| key)
+            .ok_or_else(|| ParseKeyError { name: name.to_string() })
+    }
 }
 
 impl ToPrimitive for Key {
@@ -385,246 +601,9 @@ impl ToPrimitive for Key {
 
 impl FromPrimitive for Key {
     fn from_u64(n: u64) -> Option<Key> {
-        match n {
-            0 => Some(Key::Unknown),
-            8 => Some(Key::Backspace),
-            9 => Some(Key::Tab),
-            13 => Some(Key::Return),
-            27 => Some(Key::Escape),
-            32 => Some(Key::Space),
-            33 => Some(Key::Exclaim),
-            34 => Some(Key::Quotedbl),
-            35 => Some(Key::Hash),
-            36 => Some(Key::Dollar),
-            37 => Some(Key::Percent),
-            38 => Some(Key::Ampersand),
-            39 => Some(Key::Quote),
-            40 => Some(Key::LeftParen),
-            41 => Some(Key::RightParen),
-            42 => Some(Key::Asterisk),
-            43 => Some(Key::Plus),
-            44 => Some(Key::Comma),
-            45 => Some(Key::Minus),
-            46 => Some(Key::Period),
-            47 => Some(Key::Slash),
-            48 => Some(Key::D0),
-            49 => Some(Key::D1),
-            50 => Some(Key::D2),
-            51 => Some(Key::D3),
-            52 => Some(Key::D4),
-            53 => Some(Key::D5),
-            54 => Some(Key::D6),
-            55 => Some(Key::D7),
-            56 => Some(Key::D8),
-            57 => Some(Key::D9),
-            58 => Some(Key::Colon),
-            59 => Some(Key::Semicolon),
-            60 => Some(Key::Less),
-            61 => Some(Key::Equals),
-            62 => Some(Key::Greater),
-            63 => Some(Key::Question),
-            64 => Some(Key::At),
-            91 => Some(Key::LeftBracket),
-            92 => Some(Key::Backslash),
-            93 => Some(Key::RightBracket),
-            94 => Some(Key::Caret),
-            95 => Some(Key::Underscore),
-            96 => Some(Key::Backquote),
-            97 => Some(Key::A),
-            98 => Some(Key::B),
-            99 => Some(Key::C),
-            100 => Some(Key::D),
-            101 => Some(Key::E),
-            102 => Some(Key::F),
-            103 => Some(Key::G),
-            104 => Some(Key::H),
-            105 => Some(Key::I),
-            106 => Some(Key::J),
-            107 => Some(Key::K),
-            108 => Some(Key::L),
-            109 => Some(Key::M),
-            110 => Some(Key::N),
-            111 => Some(Key::O),
-            112 => Some(Key::P),
-            113 => Some(Key::Q),
-            114 => Some(Key::R),
-            115 => Some(Key::S),
-            116 => Some(Key::T),
-            117 => Some(Key::U),
-            118 => Some(Key::V),
-            119 => Some(Key::W),
-            120 => Some(Key::X),
-            121 => Some(Key::Y),
-            122 => Some(Key::Z),
-            127 => Some(Key::Delete),
-            1073741881 => Some(Key::CapsLock),
-            1073741882 => Some(Key::F1),
-            1073741883 => Some(Key::F2),
-            1073741884 => Some(Key::F3),
-            1073741885 => Some(Key::F4),
-            1073741886 => Some(Key::F5),
-            1073741887 => Some(Key::F6),
-            1073741888 => Some(Key::F7),
-            1073741889 => Some(Key::F8),
-            1073741890 => Some(Key::F9),
-            1073741891 => Some(Key::F10),
-            1073741892 => Some(Key::F11),
-            1073741893 => Some(Key::F12),
-            1073741894 => Some(Key::PrintScreen),
-            1073741895 => Some(Key::ScrollLock),
-            1073741896 => Some(Key::Pause),
-            1073741897 => Some(Key::Insert),
-            1073741898 => Some(Key::Home),
-            1073741899 => Some(Key::PageUp),
-            1073741901 => Some(Key::End),
-            1073741902 => Some(Key::PageDown),
-            1073741903 => Some(Key::Right),
-            1073741904 => Some(Key::Left),
-            1073741905 => Some(Key::Down),
-            1073741906 => Some(Key::Up),
-            1073741907 => Some(Key::NumLockClear),
-            1073741908 => Some(Key::NumPadDivide),
-            1073741909 => Some(Key::NumPadMultiply),
-            1073741910 => Some(Key::NumPadMinus),
-            1073741911 => Some(Key::NumPadPlus),
-            1073741912 => Some(Key::NumPadEnter),
-            1073741913 => Some(Key::NumPad1),
-            1073741914 => Some(Key::NumPad2),
-            1073741915 => Some(Key::NumPad3),
-            1073741916 => Some(Key::NumPad4),
-            1073741917 => Some(Key::NumPad5),
-            1073741918 => Some(Key::NumPad6),
-            1073741919 => Some(Key::NumPad7),
-            1073741920 => Some(Key::NumPad8),
-            1073741921 => Some(Key::NumPad9),
-            1073741922 => Some(Key::NumPad0),
-            1073741923 => Some(Key::NumPadPeriod),
-            1073741925 => Some(Key::Application),
-            1073741926 => Some(Key::Power),
-            1073741927 => Some(Key::NumPadEquals),
-            1073741928 => Some(Key::F13),
-            1073741929 => Some(Key::F14),
-            1073741930 => Some(Key::F15),
-            1073741931 => Some(Key::F16),
-            1073741932 => Some(Key::F17),
-            1073741933 => Some(Key::F18),
-            1073741934 => Some(Key::F19),
-            1073741935 => Some(Key::F20),
-            1073741936 => Some(Key::F21),
-            1073741937 => Some(Key::F22),
-            1073741938 => Some(Key::F23),
-            1073741939 => Some(Key::F24),
-            1073741940 => Some(Key::Execute),
-            1073741941 => Some(Key::Help),
-            1073741942 => Some(Key::Menu),
-            1073741943 => Some(Key::Select),
-            1073741944 => Some(Key::Stop),
-            1073741945 => Some(Key::Again),
-            1073741946 => Some(Key::Undo),
-            1073741947 => Some(Key::Cut),
-            1073741948 => Some(Key::Copy),
-            1073741949 => Some(Key::Paste),
-            1073741950 => Some(Key::Find),
-            1073741951 => Some(Key::Mute),
-            1073741952 => Some(Key::VolumeUp),
-            1073741953 => Some(Key::VolumeDown),
-            1073741957 => Some(Key::NumPadComma),
-            1073741958 => Some(Key::NumPadEqualsAS400),
-            1073741977 => Some(Key::AltErase),
-            1073741978 => Some(Key::Sysreq),
-            1073741979 => Some(Key::Cancel),
-            1073741980 => Some(Key::Clear),
-            1073741981 => Some(Key::Prior),
-            1073741982 => Some(Key::Return2),
-            1073741983 => Some(Key::Separator),
-            1073741984 => Some(Key::Out),
-            1073741985 => Some(Key::Oper),
-            1073741986 => Some(Key::ClearAgain),
-            1073741987 => Some(Key::CrSel),
-            1073741988 => Some(Key::ExSel),
-            1073742000 => Some(Key::NumPad00),
-            1073742001 => Some(Key::NumPad000),
-            1073742002 => Some(Key::ThousandsSeparator),
-            1073742003 => Some(Key::DecimalSeparator),
-            1073742004 => Some(Key::CurrencyUnit),
-            1073742005 => Some(Key::CurrencySubUnit),
-            1073742006 => Some(Key::NumPadLeftParen),
-            1073742007 => Some(Key::NumPadRightParen),
-            1073742008 => Some(Key::NumPadLeftBrace),
-            1073742009 => Some(Key::NumPadRightBrace),
-            1073742010 => Some(Key::NumPadTab),
-            1073742011 => Some(Key::NumPadBackspace),
-            1073742012 => Some(Key::NumPadA),
-            1073742013 => Some(Key::NumPadB),
-            1073742014 => Some(Key::NumPadC),
-            1073742015 => Some(Key::NumPadD),
-            1073742016 => Some(Key::NumPadE),
-            1073742017 => Some(Key::NumPadF),
-            1073742018 => Some(Key::NumPadXor),
-            1073742019 => Some(Key::NumPadPower),
-            1073742020 => Some(Key::NumPadPercent),
-            1073742021 => Some(Key::NumPadLess),
-            1073742022 => Some(Key::NumPadGreater),
-            1073742023 => Some(Key::NumPadAmpersand),
-            1073742024 => Some(Key::NumPadDblAmpersand),
-            1073742025 => Some(Key::NumPadVerticalBar),
-            1073742026 => Some(Key::NumPadDblVerticalBar),
-            1073742027 => Some(Key::NumPadColon),
-            1073742028 => Some(Key::NumPadHash),
-            1073742029 => Some(Key::NumPadSpace),
-            1073742030 => Some(Key::NumPadAt),
-            1073742031 => Some(Key::NumPadExclam),
-            1073742032 => Some(Key::NumPadMemStore),
-            1073742033 => Some(Key::NumPadMemRecall),
-            1073742034 => Some(Key::NumPadMemClear),
-            1073742035 => Some(Key::NumPadMemAdd),
-            1073742036 => Some(Key::NumPadMemSubtract),
-            1073742037 => Some(Key::NumPadMemMultiply),
-            1073742038 => Some(Key::NumPadMemDivide),
-            1073742039 => Some(Key::NumPadPlusMinus),
-            1073742040 => Some(Key::NumPadClear),
-            1073742041 => Some(Key::NumPadClearEntry),
-            1073742042 => Some(Key::NumPadBinary),
-            1073742043 => Some(Key::NumPadOctal),
-            1073742044 => Some(Key::NumPadDecimal),
-            1073742045 => Some(Key::NumPadHexadecimal),
-            1073742048 => Some(Key::LCtrl),
-            1073742049 => Some(Key::LShift),
-            1073742050 => Some(Key::LAlt),
-            1073742051 => Some(Key::LGui),
-            1073742052 => Some(Key::RCtrl),
-            1073742053 => Some(Key::RShift),
-            1073742054 => Some(Key::RAlt),
-            1073742055 => Some(Key::RGui),
-            1073742081 => Some(Key::Mode),
-            1073742082 => Some(Key::AudioNext),
-            1073742083 => Some(Key::AudioPrev),
-            1073742084 => Some(Key::AudioStop),
-            1073742085 => Some(Key::AudioPlay),
-            1073742086 => Some(Key::AudioMute),
-            1073742087 => Some(Key::MediaSelect),
-            1073742088 => Some(Key::Www),
-            1073742089 => Some(Key::Mail),
-            1073742090 => Some(Key::Calculator),
-            1073742091 => Some(Key::Computer),
-            1073742092 => Some(Key::AcSearch),
-            1073742093 => Some(Key::AcHome),
-            1073742094 => Some(Key::AcBack),
-            1073742095 => Some(Key::AcForward),
-            1073742096 => Some(Key::AcStop),
-            1073742097 => Some(Key::AcRefresh),
-            1073742098 => Some(Key::AcBookmarks),
-            1073742099 => Some(Key::BrightnessDown),
-            1073742100 => Some(Key::BrightnessUp),
-            1073742101 => Some(Key::DisplaySwitch),
-            1073742102 => Some(Key::KbdIllumToggle),
-            1073742103 => Some(Key::KbdIllumDown),
-            1073742104 => Some(Key::KbdIllumUp),
-            1073742105 => Some(Key::Eject),
-            1073742106 => Some(Key::Sleep),
-
-            _ => Some(Key::Unknown)
-        }
+        KEYS.binary_search_by_key(&n, |key| key.code() as u64)
+            .ok()
+            .map(|i| KEYS[i])
     }
 
     #[inline(always)]
@@ -637,3 +616,154 @@ impl FromPrimitive for Key {
         FromPrimitive::from_u64(n as u64)
     }
 }
+
+/// Defines functions converting between keys and back-end key codes
+/// from a table.
+///
+/// Every key must be listed, either with its code or as unmapped,
+/// so adding a key to `Key` fails to compile until the table is updated.
+/// Codes without a key convert to `Key::Unknown`.
+///
+/// ```ignore
+/// key_table! {
+///     /// Converts a scancode to a key.
+///     pub fn to_key(code: u32) -> Key;
+///     /// Converts a key to a scancode.
+///     pub fn from_key(key: Key) -> Option<u32>;
+///     mapped {
+///         A => 4,
+///         B => 5,
+///         // ...
+///     }
+///     unmapped { Unknown, Sleep, /* ... */ }
+/// }
+/// ```
+#[macro_export]
+macro_rules! key_table {
+    (
+        $(#[$to_attr:meta])*
+        $to_vis:vis fn $to:ident($code:ident: $code_ty:ty) -> Key;
+        $(#[$from_attr:meta])*
+        $from_vis:vis fn $from:ident($key:ident: Key) -> Option<$from_ty:ty>;
+        mapped { $($name:ident => $value:expr,)* }
+        unmapped { $($unmapped:ident,)* }
+    ) => {
+        $(#[$to_attr])*
+        $to_vis fn $to($code: $code_ty) -> $crate::Key {
+            $(if $code == $value { return $crate::Key::$name; })*
+            $crate::Key::Unknown
+        }
+
+        $(#[$from_attr])*
+        $from_vis fn $from($key: $crate::Key) -> Option<$from_ty> {
+            match $key {
+                $($crate::Key::$name => Some($value),)*
+                $($crate::Key::$unmapped => None,)*
+            }
+        }
+    }
+}
+
+#[cfg(feature = "quickcheck")]
+impl Arbitrary for Key {
+    fn arbitrary(g: &mut Gen) -> Key {
+        *g.choose(KEYS).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_primitives() {
+        use num::{ FromPrimitive, ToPrimitive };
+
+        for pair in KEYS.windows(2) {
+            assert!(pair[0].code() < pair[1].code());
+        }
+        for &key in KEYS {
+            let code = ToPrimitive::to_u64(&key).unwrap();
+            assert_eq!(FromPrimitive::from_u64(code), Some(key));
+        }
+        assert_eq!(<Key as FromPrimitive>::from_u64(1), None);
+        assert_eq!(<Key as FromPrimitive>::from_i64(-1), None);
+    }
+
+    key_table! {
+        fn to_key(code: u32) -> Key;
+        fn from_key(key: Key) -> Option<u32>;
+        mapped {
+            A => 4,
+            B => 5,
+            Space => 44,
+        }
+        unmapped {
+            Unknown, Backspace, Tab, Return, Escape, Exclaim, Quotedbl, Hash, Dollar,
+            Percent, Ampersand, Quote, LeftParen, RightParen, Asterisk, Plus, Comma,
+            Minus, Period, Slash, D0, D1, D2, D3, D4, D5, D6, D7, D8, D9, Colon,
+            Semicolon, Less, Equals, Greater, Question, At, LeftBracket, Backslash,
+            RightBracket, Caret, Underscore, Backquote, C, D, E, F, G, H, I, J, K, L, M,
+            N, O, P, Q, R, S, T, U, V, W, X, Y, Z, Delete, CapsLock, F1, F2, F3, F4, F5,
+            F6, F7, F8, F9, F10, F11, F12, PrintScreen, ScrollLock, Pause, Insert, Home,
+            PageUp, End, PageDown, Right, Left, Down, Up, NumLockClear, NumPadDivide,
+            NumPadMultiply, NumPadMinus, NumPadPlus, NumPadEnter, NumPad1, NumPad2,
+            NumPad3, NumPad4, NumPad5, NumPad6, NumPad7, NumPad8, NumPad9, NumPad0,
+            NumPadPeriod, Application, Power, NumPadEquals, F13, F14, F15, F16, F17,
+            F18, F19, F20, F21, F22, F23, F24, Execute, Help, Menu, Select, Stop, Again,
+            Undo, Cut, Copy, Paste, Find, Mute, VolumeUp, VolumeDown, NumPadComma,
+            NumPadEqualsAS400, AltErase, Sysreq, Cancel, Clear, Prior, Return2,
+            Separator, Out, Oper, ClearAgain, CrSel, ExSel, NumPad00, NumPad000,
+            ThousandsSeparator, DecimalSeparator, CurrencyUnit, CurrencySubUnit,
+            NumPadLeftParen, NumPadRightParen, NumPadLeftBrace, NumPadRightBrace,
+            NumPadTab, NumPadBackspace, NumPadA, NumPadB, NumPadC, NumPadD, NumPadE,
+            NumPadF, NumPadXor, NumPadPower, NumPadPercent, NumPadLess, NumPadGreater,
+            NumPadAmpersand, NumPadDblAmpersand, NumPadVerticalBar,
+            NumPadDblVerticalBar, NumPadColon, NumPadHash, NumPadSpace, NumPadAt,
+            NumPadExclam, NumPadMemStore, NumPadMemRecall, NumPadMemClear, NumPadMemAdd,
+            NumPadMemSubtract, NumPadMemMultiply, NumPadMemDivide, NumPadPlusMinus,
+            NumPadClear, NumPadClearEntry, NumPadBinary, NumPadOctal, NumPadDecimal,
+            NumPadHexadecimal, LCtrl, LShift, LAlt, LGui, RCtrl, RShift, RAlt, RGui,
+            Mode, AudioNext, AudioPrev, AudioStop, AudioPlay, AudioMute, MediaSelect,
+            Www, Mail, Calculator, Computer, AcSearch, AcHome, AcBack, AcForward,
+            AcStop, AcRefresh, AcBookmarks, BrightnessDown, BrightnessUp, DisplaySwitch,
+            KbdIllumToggle, KbdIllumDown, KbdIllumUp, Eject, Sleep,
+        }
+    }
+
+    #[test]
+    fn test_key_table() {
+        assert_eq!(to_key(5), Key::B);
+        assert_eq!(to_key(1), Key::Unknown);
+        assert_eq!(from_key(Key::Space), Some(44));
+        assert_eq!(from_key(Key::Return), None);
+    }
+
+    #[test]
+    fn test_parse_key() {
+        assert_eq!("space".parse(), Ok(Key::Space));
+        assert_eq!("NumPad8".parse(), Ok(Key::NumPad8));
+        assert_eq!("Nope".parse::<Key>(), Err(ParseKeyError { name: "Nope".to_string() }));
+    }
+
+    #[test]
+    fn test_alt_gr() {
+        let mut modifiers = NO_MODIFIER;
+        modifiers.handle_input(&Input::Press(Button::Keyboard(Key::LCtrl)));
+        modifiers.handle_input(&Input::Press(Button::Keyboard(Key::LAlt)));
+        assert!(!modifiers.is_alt_gr(false));
+        assert!(modifiers.is_alt_gr(true));
+        modifiers.handle_input(&Input::Focus(false));
+        modifiers.handle_input(&Input::Press(Button::Keyboard(Key::Mode)));
+        assert!(modifiers.is_alt_gr(false));
+    }
+
+    #[test]
+    fn test_resolve_numpad() {
+        assert_eq!(Key::NumPad8.resolve_numpad(true), Key::D8);
+        assert_eq!(Key::NumPad8.resolve_numpad(false), Key::Up);
+        assert_eq!(Key::NumPad5.resolve_numpad(false), Key::NumPad5);
+        assert_eq!(Key::NumPadEnter.resolve_numpad(false), Key::Return);
+        assert_eq!(Key::A.resolve_numpad(true), Key::A);
+    }
+}
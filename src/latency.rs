@@ -0,0 +1,118 @@
+//! Simulation of network latency, e.g. for testing netcode.
+
+use std::f64::consts::PI;
+
+use Input;
+use stress;
+
+/// A distribution of delays in seconds.
+#[derive(Copy, Clone, RustcDecodable, RustcEncodable, PartialEq, Debug)]
+pub enum Delay {
+    /// A constant delay.
+    Constant(f64),
+    /// A delay uniformly distributed between a minimum and a maximum.
+    Uniform(f64, f64),
+    /// A normally distributed delay with a mean and a standard deviation.
+    Normal(f64, f64),
+}
+
+impl Delay {
+    // Samples a delay, never negative.
+    fn sample(&self, rng: &mut u64) -> f64 {
+        let delay = match *self {
+            Delay::Constant(delay) => delay,
+            Delay::Uniform(min, max) => min + (max - min) * stress::random(rng),
+            Delay::Normal(mean, std_dev) => {
+                // Box-Muller transform.
+                let u = 1.0 - stress::random(rng);
+                let v = stress::random(rng);
+                mean + std_dev * (-2.0 * u.ln()).sqrt() * (2.0 * PI * v).cos()
+            }
+        };
+        delay.max(0.0)
+    }
+}
+
+/// Delays and jitters the delivery of input.
+///
+/// The delays are deterministic for a given seed.
+#[derive(Clone, Debug)]
+pub struct LatencySimulator {
+    /// The distribution of delays.
+    pub delay: Delay,
+    /// Whether inputs are delivered in order, like over TCP.
+    ///
+    /// Otherwise jitter can reorder inputs, like over UDP.
+    pub keep_order: bool,
+    rng: u64,
+    time: f64,
+    // Delivery time and input, in the order received.
+    queue: Vec<(f64, Input)>,
+}
+
+impl LatencySimulator {
+    /// Creates a new simulator delivering inputs in order.
+    pub fn new(seed: u64, delay: Delay) -> LatencySimulator {
+        LatencySimulator {
+            delay: delay,
+            keep_order: true,
+            // The random generator state must not be zero.
+            rng: seed | 1,
+            time: 0.0,
+            queue: vec![],
+        }
+    }
+
+    /// Queues an input for delayed delivery.
+    pub fn handle_input(&mut self, input: &Input) {
+        let mut due = self.time + self.delay.sample(&mut self.rng);
+        if self.keep_order {
+            if let Some(&(last, _)) = self.queue.last() {
+                due = due.max(last);
+            }
+        }
+        self.queue.push((due, input.clone()));
+    }
+
+    /// Returns the number of inputs on their way.
+    pub fn in_flight(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Advances time and returns the delivered inputs.
+    pub fn update(&mut self, dt: f64) -> Vec<Input> {
+        self.time += dt;
+        let time = self.time;
+        let mut delivered: Vec<(f64, Input)> = vec![];
+        let mut i = 0;
+        while i < self.queue.len() {
+            if self.queue[i].0 <= time {
+                delivered.push(self.queue.remove(i));
+            } else {
+                i += 1;
+            }
+        }
+        // Stable sort keeps the received order for equal times.
+        delivered.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        delivered.into_iter().map(|(_, input)| input).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Input;
+
+    #[test]
+    fn test_keep_order() {
+        let mut latency = LatencySimulator::new(7, Delay::Uniform(0.05, 0.15));
+        for i in 0..20 {
+            latency.handle_input(&Input::Resize(i, i));
+        }
+        let delivered = latency.update(0.1);
+        assert!(latency.in_flight() > 0);
+        let delivered: Vec<Input> = delivered.into_iter().chain(latency.update(0.1)).collect();
+        let expected: Vec<Input> = (0..20).map(|i| Input::Resize(i, i)).collect();
+        assert_eq!(delivered, expected);
+    }
+}
@@ -4,17 +4,96 @@
 
 //! A flexible structure for user interactions
 //! to be used in window frameworks and widgets libraries.
+//!
+//! The core types, `Input`, `Button`, `Motion` and the devices in
+//! `keyboard`, `mouse`, `gamepad` and `touch`, are stable.
+//! Experimental extensions are behind features, all enabled by default
+//! through the `experimental` feature:
+//!
+//! - `mapping`: actions, bindings import, sensitivity and directions
+//! - `haptics`: rumble
+//! - `emulation`: emulating devices with other devices
+//! - `netcode`: remote input, rollback and latency simulation
+//! - `analysis`: heatmaps
+//!
+//! Back-ends can depend on the core alone with `default-features = false`.
 
 #[macro_use]
 extern crate bitflags;
 extern crate rustc_serialize;
 extern crate num;
+#[cfg(feature = "quickcheck")]
+extern crate quickcheck;
+#[cfg(feature = "log")]
+#[macro_use]
+extern crate log;
 
+pub use gamepad::{ Battery, GamepadAxis, GamepadButton };
+pub use instrument::DrumPad;
 pub use mouse::MouseButton;
 pub use keyboard::Key;
+pub use state::InputState;
+pub use touch::{ PenHover, StylusButton, Touch };
+
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
 
+#[cfg(feature = "quickcheck")]
+use quickcheck::{ Arbitrary, Gen };
+
+pub mod accessibility;
+#[cfg(feature = "mapping")]
+pub mod action;
+pub mod active;
+pub mod checksum;
+pub mod clock;
+pub mod conformance;
+#[cfg(feature = "mapping")]
+pub mod direction;
+#[cfg(feature = "emulation")]
+pub mod emulate;
+pub mod export;
+pub mod gamepad;
+pub mod generic;
+pub mod golden;
+#[cfg(feature = "analysis")]
+pub mod heatmap;
+pub mod idle;
+#[cfg(feature = "mapping")]
+pub mod import;
+pub mod instrument;
 pub mod keyboard;
+#[cfg(feature = "netcode")]
+pub mod latency;
+#[cfg(feature = "log")]
+pub mod logging;
 pub mod mouse;
+pub mod names;
+pub mod pool;
+pub mod prelude;
+pub mod queue;
+pub mod recording;
+pub mod remap;
+#[cfg(feature = "netcode")]
+pub mod remote;
+pub mod repeat;
+#[cfg(feature = "netcode")]
+pub mod rollback;
+#[cfg(feature = "haptics")]
+pub mod rumble;
+pub mod script;
+pub mod secure;
+#[cfg(feature = "mapping")]
+pub mod sensitivity;
+pub mod state;
+pub mod stats;
+pub mod stress;
+pub mod touch;
+pub mod turbo;
+pub mod wheel;
+pub mod wire;
 
 /// Models different kinds of buttons.
 #[derive(Copy, Clone, RustcDecodable, RustcEncodable, PartialEq, Eq, Hash, Debug)]
@@ -23,6 +102,193 @@ pub enum Button {
     Keyboard(Key),
     /// A mouse button.
     Mouse(MouseButton),
+    /// A stylus button.
+    Stylus(StylusButton),
+    /// A gamepad button, with the id of the gamepad.
+    ///
+    /// Bindings, e.g. of `ActionMap`, `Turbo` and `ButtonNames`, are
+    /// pad-independent and match the button of any gamepad. They are
+    /// keyed on the button with the id 0, see `Button::any_gamepad`.
+    Gamepad(u32, GamepadButton),
+}
+
+impl Button {
+    /// Returns an identifier for the button prompt icon to show in UIs.
+    ///
+    /// For example `"kbd_space"` or `"mouse_left"`. Buttons unknown to
+    /// this crate use their code, e.g. `"mouse_button12"`.
+    pub fn prompt_name(&self) -> String {
+        match *self {
+            Button::Keyboard(key) =>
+                format!("kbd_{}", format!("{:?}", key).to_lowercase()),
+            Button::Mouse(MouseButton::Other(n)) => format!("mouse_button{}", n),
+            Button::Mouse(button) =>
+                format!("mouse_{}", format!("{:?}", button).to_lowercase()),
+            Button::Stylus(button) =>
+                format!("stylus_{}", format!("{:?}", button).to_lowercase()),
+            Button::Gamepad(_, GamepadButton::Other(n)) => format!("gamepad_other{}", n),
+            Button::Gamepad(_, button) =>
+                format!("gamepad_{}", format!("{:?}", button).to_lowercase()),
+        }
+    }
+
+    /// Returns the button with a prompt name.
+    ///
+    /// Gamepad buttons are returned pad-independent, with the gamepad id 0.
+    pub fn from_prompt_name(name: &str) -> Option<Button> {
+        use num::FromPrimitive;
+
+        // Parses the code of a button, written without leading zeros.
+        fn code(name: &str, prefix: &str) -> Option<u32> {
+            let digits = name.strip_prefix(prefix)?;
+            digits.parse().ok().filter(|n: &u32| n.to_string() == digits)
+        }
+
+        // Prompt names are lowercase.
+        if name.bytes().any(|b| b.is_ascii_uppercase()) { return None; }
+        if let Some(name) = name.strip_prefix("kbd_") {
+            keyboard::KEYS.iter().zip(keyboard::KEY_NAMES)
+                .find(|&(_, key_name)| key_name.eq_ignore_ascii_case(name))
+                .map(|(&key, _)| Button::Keyboard(key))
+        } else if let Some(name) = name.strip_prefix("mouse_") {
+            match name {
+                "unknown" => Some(MouseButton::Unknown),
+                "left" => Some(MouseButton::Left),
+                "right" => Some(MouseButton::Right),
+                "middle" => Some(MouseButton::Middle),
+                "x1" => Some(MouseButton::X1),
+                "x2" => Some(MouseButton::X2),
+                // Named buttons above 5 and unknown buttons.
+                _ => code(name, "button")
+                    .filter(|&n| n > 5)
+                    .and_then(|n| MouseButton::from_u64(n as u64)),
+            }.map(Button::Mouse)
+        } else if let Some(name) = name.strip_prefix("stylus_") {
+            match name {
+                "tip" => Some(StylusButton::Tip),
+                "eraser" => Some(StylusButton::Eraser),
+                "barrel1" => Some(StylusButton::Barrel1),
+                "barrel2" => Some(StylusButton::Barrel2),
+                _ => None,
+            }.map(Button::Stylus)
+        } else if let Some(name) = name.strip_prefix("gamepad_") {
+            gamepad::BUTTONS.iter().cloned()
+                .find(|button| {
+                    // Vendor neutral names without spaces and dashes,
+                    // e.g. "D-Pad Up" as "dpadup".
+                    button.name().chars()
+                        .filter(|c| c.is_ascii_alphanumeric())
+                        .map(|c| c.to_ascii_lowercase())
+                        .eq(name.chars())
+                })
+                .or_else(|| code(name, "other").map(GamepadButton::Other))
+                .map(|button| Button::Gamepad(0, button))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the key if this is a keyboard button.
+    pub fn as_key(&self) -> Option<Key> {
+        match *self {
+            Button::Keyboard(key) => Some(key),
+            _ => None,
+        }
+    }
+
+    /// Returns the mouse button if this is a mouse button.
+    pub fn as_mouse(&self) -> Option<MouseButton> {
+        match *self {
+            Button::Mouse(button) => Some(button),
+            _ => None,
+        }
+    }
+
+    /// Returns the gamepad id and button if this is a gamepad button.
+    pub fn as_gamepad(&self) -> Option<(u32, GamepadButton)> {
+        match *self {
+            Button::Gamepad(id, button) => Some((id, button)),
+            _ => None,
+        }
+    }
+
+    /// Returns the button with another gamepad id.
+    ///
+    /// Buttons of other devices are returned unchanged.
+    pub fn with_gamepad(self, id: u32) -> Button {
+        match self {
+            Button::Gamepad(_, button) => Button::Gamepad(id, button),
+            button => button,
+        }
+    }
+
+    /// Returns the pad-independent button, with the gamepad id 0,
+    /// which bindings are keyed on.
+    pub fn any_gamepad(self) -> Button {
+        self.with_gamepad(0)
+    }
+}
+
+/// An error parsing a button name.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ParseButtonError {
+    /// The name that is not a button.
+    pub name: String,
+}
+
+impl fmt::Display for ParseButtonError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unknown button `{}`", self.name)
+    }
+}
+
+impl Error for ParseButtonError {}
+
+/// Parses a button from its prompt name, e.g. `"gamepad_south"`,
+/// or a key name like `"Space"`, ignoring case.
+impl FromStr for Button {
+    type Err = ParseButtonError;
+
+    fn from_str(name: &str) -> Result<Button, ParseButtonError> {
+        Button::from_prompt_name(&name.to_lowercase())
+            .or_else(|| name.parse().ok().map(Button::Keyboard))
+            .ok_or_else(|| ParseButtonError { name: name.to_string() })
+    }
+}
+
+impl From<Key> for Button {
+    fn from(key: Key) -> Button { Button::Keyboard(key) }
+}
+
+impl From<MouseButton> for Button {
+    fn from(button: MouseButton) -> Button { Button::Mouse(button) }
+}
+
+impl From<StylusButton> for Button {
+    fn from(button: StylusButton) -> Button { Button::Stylus(button) }
+}
+
+impl fmt::Display for Button {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Button::Keyboard(key) => write!(f, "key {:?}", key),
+            Button::Mouse(button) => write!(f, "mouse {:?}", button),
+            Button::Stylus(button) => write!(f, "stylus {:?}", button),
+            Button::Gamepad(id, button) => write!(f, "gamepad{} {:?}", id, button),
+        }
+    }
+}
+
+#[cfg(feature = "quickcheck")]
+impl Arbitrary for Button {
+    fn arbitrary(g: &mut Gen) -> Button {
+        match u8::arbitrary(g) % 4 {
+            0 => Button::Keyboard(Key::arbitrary(g)),
+            1 => Button::Mouse(MouseButton::arbitrary(g)),
+            2 => Button::Stylus(StylusButton::arbitrary(g)),
+            _ => Button::Gamepad(u32::arbitrary(g), GamepadButton::arbitrary(g)),
+        }
+    }
 }
 
 /// Models different kinds of motion.
@@ -34,6 +300,81 @@ pub enum Motion {
     MouseRelative(f64, f64),
     /// x and y in scroll ticks.
     MouseScroll(f64, f64),
+    /// Position of a gamepad axis, with the id of the gamepad.
+    GamepadAxis(u32, GamepadAxis, f64),
+    /// Angular velocity of a gamepad around the x, y and z axes
+    /// in radians per second, with the id of the gamepad.
+    GamepadGyro(u32, [f64; 3]),
+    /// Acceleration of a gamepad along the x, y and z axes
+    /// in meters per second squared, with the id of the gamepad.
+    GamepadAccel(u32, [f64; 3]),
+}
+
+#[cfg(feature = "quickcheck")]
+impl Arbitrary for Motion {
+    fn arbitrary(g: &mut Gen) -> Motion {
+        let (x, y) = (f64::arbitrary(g), f64::arbitrary(g));
+        let id = u32::arbitrary(g);
+        let xyz = [x, y, f64::arbitrary(g)];
+        match u8::arbitrary(g) % 6 {
+            0 => Motion::MouseCursor(x, y),
+            1 => Motion::MouseRelative(x, y),
+            2 => Motion::MouseScroll(x, y),
+            3 => Motion::GamepadAxis(id, GamepadAxis::arbitrary(g), x),
+            4 => Motion::GamepadGyro(id, xyz),
+            _ => Motion::GamepadAccel(id, xyz),
+        }
+    }
+}
+
+impl Motion {
+    /// Returns the position if this is a mouse cursor motion.
+    pub fn as_mouse_cursor(&self) -> Option<[f64; 2]> {
+        match *self {
+            Motion::MouseCursor(x, y) => Some([x, y]),
+            _ => None,
+        }
+    }
+
+    /// Returns the offset if this is a relative mouse motion.
+    pub fn as_mouse_relative(&self) -> Option<[f64; 2]> {
+        match *self {
+            Motion::MouseRelative(x, y) => Some([x, y]),
+            _ => None,
+        }
+    }
+
+    /// Returns the scroll ticks if this is a mouse scroll.
+    pub fn as_mouse_scroll(&self) -> Option<[f64; 2]> {
+        match *self {
+            Motion::MouseScroll(x, y) => Some([x, y]),
+            _ => None,
+        }
+    }
+
+    /// Returns the gamepad id, axis and position if this is a gamepad axis motion.
+    pub fn as_gamepad_axis(&self) -> Option<(u32, GamepadAxis, f64)> {
+        match *self {
+            Motion::GamepadAxis(id, axis, value) => Some((id, axis, value)),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Motion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Motion::MouseCursor(x, y) => write!(f, "cursor {} {}", x, y),
+            Motion::MouseRelative(x, y) => write!(f, "relative {} {}", x, y),
+            Motion::MouseScroll(x, y) => write!(f, "scroll {} {}", x, y),
+            Motion::GamepadAxis(id, axis, value) =>
+                write!(f, "gamepad{} {:?} {}", id, axis, value),
+            Motion::GamepadGyro(id, v) =>
+                write!(f, "gamepad{} gyro {} {} {}", id, v[0], v[1], v[2]),
+            Motion::GamepadAccel(id, v) =>
+                write!(f, "gamepad{} accel {} {} {}", id, v[0], v[1], v[2]),
+        }
+    }
 }
 
 /// Models input events.
@@ -51,4 +392,514 @@ pub enum Input {
     Resize(u32, u32),
     /// Window gained or lost focus.
     Focus(bool),
+    /// On-screen keyboard was shown or hidden.
+    OnScreenKeyboard(bool),
+    /// Touch contact began, moved or ended.
+    Touch(Touch),
+    /// Stylus hovered above the surface.
+    PenHover(PenHover),
+    /// Touch contact on a gamepad touchpad, with the id of the gamepad.
+    ///
+    /// Positions are from 0 to 1 across the touchpad.
+    GamepadTouch(u32, Touch),
+    /// Battery state of a gamepad changed, with the id of the gamepad.
+    GamepadBattery(u32, Battery),
+    /// Gamepad was connected or disconnected, with the id of the gamepad.
+    GamepadConnected(u32, bool),
+    /// Drum pad was hit with a velocity from 0 to 1,
+    /// with the id of the gamepad.
+    ///
+    /// Followed by a press of the pad's gamepad button.
+    DrumHit(u32, DrumPad, f64),
+}
+
+#[cfg(feature = "quickcheck")]
+impl Arbitrary for Input {
+    fn arbitrary(g: &mut Gen) -> Input {
+        match u8::arbitrary(g) % 13 {
+            0 => Input::Press(Button::arbitrary(g)),
+            1 => Input::Release(Button::arbitrary(g)),
+            2 => Input::Move(Motion::arbitrary(g)),
+            3 => Input::Text(String::arbitrary(g)),
+            4 => Input::Resize(u32::arbitrary(g), u32::arbitrary(g)),
+            5 => Input::Focus(bool::arbitrary(g)),
+            6 => Input::OnScreenKeyboard(bool::arbitrary(g)),
+            7 => Input::Touch(Touch::arbitrary(g)),
+            8 => Input::PenHover(PenHover::arbitrary(g)),
+            9 => Input::GamepadTouch(u32::arbitrary(g), Touch::arbitrary(g)),
+            10 => Input::GamepadBattery(u32::arbitrary(g), Battery::arbitrary(g)),
+            11 => Input::GamepadConnected(u32::arbitrary(g), bool::arbitrary(g)),
+            _ => Input::DrumHit(u32::arbitrary(g), DrumPad::arbitrary(g),
+                                f64::arbitrary(g)),
+        }
+    }
+}
+
+impl Input {
+    /// Creates a press of a keyboard key.
+    pub fn key_press(key: Key) -> Input {
+        Input::Press(Button::Keyboard(key))
+    }
+
+    /// Creates a release of a keyboard key.
+    pub fn key_release(key: Key) -> Input {
+        Input::Release(Button::Keyboard(key))
+    }
+
+    /// Creates a press of a mouse button.
+    pub fn mouse_press(button: MouseButton) -> Input {
+        Input::Press(Button::Mouse(button))
+    }
+
+    /// Creates a release of a mouse button.
+    pub fn mouse_release(button: MouseButton) -> Input {
+        Input::Release(Button::Mouse(button))
+    }
+
+    /// Creates a move of the mouse cursor to a position in window coordinates.
+    pub fn move_to(x: f64, y: f64) -> Input {
+        Input::Move(Motion::MouseCursor(x, y))
+    }
+
+    /// Creates a relative mouse motion.
+    pub fn move_by(dx: f64, dy: f64) -> Input {
+        Input::Move(Motion::MouseRelative(dx, dy))
+    }
+
+    /// Creates a mouse scroll.
+    pub fn scroll(x: f64, y: f64) -> Input {
+        Input::Move(Motion::MouseScroll(x, y))
+    }
+
+    /// Creates a press of a gamepad button.
+    pub fn gamepad_press(id: u32, button: GamepadButton) -> Input {
+        Input::Press(Button::Gamepad(id, button))
+    }
+
+    /// Creates a release of a gamepad button.
+    pub fn gamepad_release(id: u32, button: GamepadButton) -> Input {
+        Input::Release(Button::Gamepad(id, button))
+    }
+
+    /// Creates a move of a gamepad axis.
+    pub fn gamepad_axis(id: u32, axis: GamepadAxis, value: f64) -> Input {
+        Input::Move(Motion::GamepadAxis(id, axis, value))
+    }
+
+    /// Returns the pressed button.
+    pub fn as_press(&self) -> Option<Button> {
+        match *self {
+            Input::Press(button) => Some(button),
+            _ => None,
+        }
+    }
+
+    /// Returns the released button.
+    pub fn as_release(&self) -> Option<Button> {
+        match *self {
+            Input::Release(button) => Some(button),
+            _ => None,
+        }
+    }
+
+    /// Returns the pressed key.
+    pub fn as_key_press(&self) -> Option<Key> {
+        self.as_press().and_then(|button| button.as_key())
+    }
+
+    /// Returns the released key.
+    pub fn as_key_release(&self) -> Option<Key> {
+        self.as_release().and_then(|button| button.as_key())
+    }
+
+    /// Returns the pressed mouse button.
+    pub fn as_mouse_press(&self) -> Option<MouseButton> {
+        self.as_press().and_then(|button| button.as_mouse())
+    }
+
+    /// Returns the released mouse button.
+    pub fn as_mouse_release(&self) -> Option<MouseButton> {
+        self.as_release().and_then(|button| button.as_mouse())
+    }
+
+    /// Returns the motion.
+    pub fn as_move(&self) -> Option<Motion> {
+        match *self {
+            Input::Move(motion) => Some(motion),
+            _ => None,
+        }
+    }
+
+    /// Returns the mouse cursor position.
+    pub fn as_mouse_move(&self) -> Option<[f64; 2]> {
+        self.as_move().and_then(|motion| motion.as_mouse_cursor())
+    }
+
+    /// Returns the text.
+    pub fn as_text(&self) -> Option<&str> {
+        match *self {
+            Input::Text(ref text) => Some(text),
+            _ => None,
+        }
+    }
+
+    /// Returns the input with the gamepad id replaced by `f(id)`.
+    pub fn map_gamepad_id<F>(&self, f: F) -> Input
+        where F: Fn(u32) -> u32
+    {
+        match *self {
+            Input::Press(Button::Gamepad(id, button)) =>
+                Input::Press(Button::Gamepad(f(id), button)),
+            Input::Release(Button::Gamepad(id, button)) =>
+                Input::Release(Button::Gamepad(f(id), button)),
+            Input::Move(Motion::GamepadAxis(id, axis, value)) =>
+                Input::Move(Motion::GamepadAxis(f(id), axis, value)),
+            Input::Move(Motion::GamepadGyro(id, xyz)) =>
+                Input::Move(Motion::GamepadGyro(f(id), xyz)),
+            Input::Move(Motion::GamepadAccel(id, xyz)) =>
+                Input::Move(Motion::GamepadAccel(f(id), xyz)),
+            Input::GamepadTouch(id, touch) => Input::GamepadTouch(f(id), touch),
+            Input::GamepadBattery(id, battery) => Input::GamepadBattery(f(id), battery),
+            Input::GamepadConnected(id, connected) =>
+                Input::GamepadConnected(f(id), connected),
+            Input::DrumHit(id, pad, velocity) => Input::DrumHit(f(id), pad, velocity),
+            ref x => x.clone(),
+        }
+    }
+
+    /// Returns a borrowed view of the input.
+    pub fn to_ref<'a>(&'a self) -> InputRef<'a> {
+        match *self {
+            Input::Press(button) => InputRef::Press(button),
+            Input::Release(button) => InputRef::Release(button),
+            Input::Move(motion) => InputRef::Move(motion),
+            Input::Text(ref text) => InputRef::Text(text),
+            Input::Resize(w, h) => InputRef::Resize(w, h),
+            Input::Focus(focus) => InputRef::Focus(focus),
+            Input::OnScreenKeyboard(shown) => InputRef::OnScreenKeyboard(shown),
+            Input::Touch(touch) => InputRef::Touch(touch),
+            Input::PenHover(hover) => InputRef::PenHover(hover),
+            Input::GamepadTouch(id, touch) => InputRef::GamepadTouch(id, touch),
+            Input::GamepadBattery(id, battery) => InputRef::GamepadBattery(id, battery),
+            Input::GamepadConnected(id, connected) => InputRef::GamepadConnected(id, connected),
+            Input::DrumHit(id, pad, velocity) => InputRef::DrumHit(id, pad, velocity),
+        }
+    }
+}
+
+impl From<Motion> for Input {
+    fn from(motion: Motion) -> Input { Input::Move(motion) }
+}
+
+impl From<Touch> for Input {
+    fn from(touch: Touch) -> Input { Input::Touch(touch) }
+}
+
+impl From<PenHover> for Input {
+    fn from(hover: PenHover) -> Input { Input::PenHover(hover) }
+}
+
+impl TryFrom<Input> for Motion {
+    type Error = Input;
+
+    /// Returns the input back if it is not a motion.
+    fn try_from(input: Input) -> Result<Motion, Input> {
+        match input {
+            Input::Move(motion) => Ok(motion),
+            x => Err(x),
+        }
+    }
+}
+
+impl TryFrom<Input> for Touch {
+    type Error = Input;
+
+    /// Returns the input back if it is not a touch.
+    fn try_from(input: Input) -> Result<Touch, Input> {
+        match input {
+            Input::Touch(touch) => Ok(touch),
+            x => Err(x),
+        }
+    }
+}
+
+impl TryFrom<Input> for PenHover {
+    type Error = Input;
+
+    /// Returns the input back if it is not a stylus hover.
+    fn try_from(input: Input) -> Result<PenHover, Input> {
+        match input {
+            Input::PenHover(hover) => Ok(hover),
+            x => Err(x),
+        }
+    }
+}
+
+impl<'a> From<&'a Input> for InputRef<'a> {
+    fn from(input: &'a Input) -> InputRef<'a> { input.to_ref() }
+}
+
+impl<'a> From<InputRef<'a>> for Input {
+    fn from(input: InputRef<'a>) -> Input { input.to_input() }
+}
+
+/// Formats the input as a concise line, e.g. `press key LShift`.
+impl fmt::Display for Input {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.to_ref().fmt(f)
+    }
+}
+
+/// A borrowed view of an input event.
+///
+/// Can be handed out without cloning the payload.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum InputRef<'a> {
+    /// Pressed a button.
+    Press(Button),
+    /// Released a button.
+    Release(Button),
+    /// Moved mouse cursor.
+    Move(Motion),
+    /// Text (usually from keyboard).
+    Text(&'a str),
+    /// Window got resized.
+    Resize(u32, u32),
+    /// Window gained or lost focus.
+    Focus(bool),
+    /// On-screen keyboard was shown or hidden.
+    OnScreenKeyboard(bool),
+    /// Touch contact began, moved or ended.
+    Touch(Touch),
+    /// Stylus hovered above the surface.
+    PenHover(PenHover),
+    /// Touch contact on a gamepad touchpad, with the id of the gamepad.
+    ///
+    /// Positions are from 0 to 1 across the touchpad.
+    GamepadTouch(u32, Touch),
+    /// Battery state of a gamepad changed, with the id of the gamepad.
+    GamepadBattery(u32, Battery),
+    /// Gamepad was connected or disconnected, with the id of the gamepad.
+    GamepadConnected(u32, bool),
+    /// Drum pad was hit with a velocity from 0 to 1,
+    /// with the id of the gamepad.
+    ///
+    /// Followed by a press of the pad's gamepad button.
+    DrumHit(u32, DrumPad, f64),
+}
+
+impl<'a> InputRef<'a> {
+    /// Creates an owned input for storage.
+    pub fn to_input(self) -> Input {
+        match self {
+            InputRef::Press(button) => Input::Press(button),
+            InputRef::Release(button) => Input::Release(button),
+            InputRef::Move(motion) => Input::Move(motion),
+            InputRef::Text(text) => Input::Text(text.to_string()),
+            InputRef::Resize(w, h) => Input::Resize(w, h),
+            InputRef::Focus(focus) => Input::Focus(focus),
+            InputRef::OnScreenKeyboard(shown) => Input::OnScreenKeyboard(shown),
+            InputRef::Touch(touch) => Input::Touch(touch),
+            InputRef::PenHover(hover) => Input::PenHover(hover),
+            InputRef::GamepadTouch(id, touch) => Input::GamepadTouch(id, touch),
+            InputRef::GamepadBattery(id, battery) => Input::GamepadBattery(id, battery),
+            InputRef::GamepadConnected(id, connected) => Input::GamepadConnected(id, connected),
+            InputRef::DrumHit(id, pad, velocity) => Input::DrumHit(id, pad, velocity),
+        }
+    }
+}
+
+impl<'a> fmt::Display for InputRef<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            InputRef::Press(button) => write!(f, "press {}", button),
+            InputRef::Release(button) => write!(f, "release {}", button),
+            InputRef::Move(motion) => write!(f, "move {}", motion),
+            InputRef::Text(text) => write!(f, "text {:?}", text),
+            InputRef::Resize(w, h) => write!(f, "resize {}x{}", w, h),
+            InputRef::Focus(focus) => write!(f, "focus {}", focus),
+            InputRef::OnScreenKeyboard(shown) => write!(f, "on-screen keyboard {}", shown),
+            InputRef::Touch(touch) => write!(f, "touch {} {:?} {} {}", touch.id,
+                touch.phase, touch.position[0], touch.position[1]),
+            InputRef::PenHover(PenHover::InRange) => write!(f, "pen hover in range"),
+            InputRef::PenHover(PenHover::Move(pos, _)) =>
+                write!(f, "pen hover {} {}", pos[0], pos[1]),
+            InputRef::PenHover(PenHover::OutOfRange) => write!(f, "pen hover out of range"),
+            InputRef::GamepadTouch(id, touch) => write!(f, "gamepad{} touch {} {:?} {} {}",
+                id, touch.id, touch.phase, touch.position[0], touch.position[1]),
+            InputRef::GamepadBattery(id, battery) => {
+                write!(f, "gamepad{} battery ", id)?;
+                match battery.percent {
+                    Some(percent) => write!(f, "{}%", percent)?,
+                    None => write!(f, "unknown")?,
+                }
+                if battery.charging { write!(f, " charging")?; }
+                Ok(())
+            }
+            InputRef::GamepadConnected(id, connected) => write!(f, "gamepad{} {}", id,
+                if connected { "connected" } else { "disconnected" }),
+            InputRef::DrumHit(id, pad, velocity) =>
+                write!(f, "gamepad{} drum {:?} {}", id, pad, velocity),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prompt_names() {
+        use num::FromPrimitive;
+
+        let mut buttons: Vec<Button> = vec![];
+        buttons.extend(keyboard::KEYS.iter().map(|&key| Button::Keyboard(key)));
+        buttons.extend((0..12).filter_map(MouseButton::from_u64).map(Button::Mouse));
+        buttons.push(Button::Mouse(MouseButton::Other(u32::max_value())));
+        buttons.extend([StylusButton::Tip, StylusButton::Eraser,
+                        StylusButton::Barrel1, StylusButton::Barrel2]
+                        .iter().map(|&button| Button::Stylus(button)));
+        buttons.extend(gamepad::BUTTONS.iter().map(|&button| Button::Gamepad(0, button)));
+        buttons.push(Button::Gamepad(0, GamepadButton::Other(300)));
+        for button in buttons {
+            assert_eq!(Button::from_prompt_name(&button.prompt_name()), Some(button));
+        }
+        assert_eq!(Button::Mouse(MouseButton::Other(12)).prompt_name(), "mouse_button12");
+        assert_eq!(Button::Gamepad(3, GamepadButton::DPadUp).prompt_name(), "gamepad_dpadup");
+        assert_eq!(Button::from_prompt_name("mouse_button012"), None);
+        assert_eq!(Button::from_prompt_name("mouse_button3"), None);
+        assert_eq!(Button::from_prompt_name("kbd_Space"), None);
+        assert_eq!("kbd_Space".parse(), Ok(Button::Keyboard(Key::Space)));
+    }
+
+    #[test]
+    fn test_constructors() {
+        let south = GamepadButton::South;
+        assert_eq!(Input::key_press(Key::A), Input::Press(Button::Keyboard(Key::A)));
+        assert_eq!(Input::key_release(Key::A), Input::Release(Button::Keyboard(Key::A)));
+        assert_eq!(Input::mouse_press(MouseButton::Left),
+                   Input::Press(Button::Mouse(MouseButton::Left)));
+        assert_eq!(Input::mouse_release(MouseButton::Left),
+                   Input::Release(Button::Mouse(MouseButton::Left)));
+        assert_eq!(Input::move_to(1.0, 2.0), Input::Move(Motion::MouseCursor(1.0, 2.0)));
+        assert_eq!(Input::move_by(1.0, 2.0), Input::Move(Motion::MouseRelative(1.0, 2.0)));
+        assert_eq!(Input::scroll(0.0, 1.0), Input::Move(Motion::MouseScroll(0.0, 1.0)));
+        assert_eq!(Input::gamepad_press(1, south), Input::Press(Button::Gamepad(1, south)));
+        assert_eq!(Input::gamepad_release(1, south), Input::Release(Button::Gamepad(1, south)));
+        assert_eq!(Input::gamepad_axis(1, GamepadAxis::LeftStickX, 0.5),
+                   Input::Move(Motion::GamepadAxis(1, GamepadAxis::LeftStickX, 0.5)));
+    }
+
+    #[test]
+    fn test_accessors() {
+        let key = Button::Keyboard(Key::A);
+        let left = Button::Mouse(MouseButton::Left);
+        let south = Button::Gamepad(1, GamepadButton::South);
+        assert_eq!(key.as_key(), Some(Key::A));
+        assert_eq!(key.as_mouse(), None);
+        assert_eq!(left.as_mouse(), Some(MouseButton::Left));
+        assert_eq!(south.as_gamepad(), Some((1, GamepadButton::South)));
+        assert_eq!(south.as_key(), None);
+
+        assert_eq!(Motion::MouseCursor(1.0, 2.0).as_mouse_cursor(), Some([1.0, 2.0]));
+        assert_eq!(Motion::MouseRelative(1.0, 2.0).as_mouse_relative(), Some([1.0, 2.0]));
+        assert_eq!(Motion::MouseScroll(1.0, 2.0).as_mouse_scroll(), Some([1.0, 2.0]));
+        assert_eq!(Motion::MouseScroll(1.0, 2.0).as_mouse_cursor(), None);
+        assert_eq!(Motion::GamepadAxis(1, GamepadAxis::LeftTrigger, 0.5).as_gamepad_axis(),
+                   Some((1, GamepadAxis::LeftTrigger, 0.5)));
+
+        assert_eq!(Input::Press(key).as_press(), Some(key));
+        assert_eq!(Input::Press(key).as_release(), None);
+        assert_eq!(Input::Release(key).as_release(), Some(key));
+        assert_eq!(Input::Press(key).as_key_press(), Some(Key::A));
+        assert_eq!(Input::Release(key).as_key_release(), Some(Key::A));
+        assert_eq!(Input::Press(left).as_key_press(), None);
+        assert_eq!(Input::Press(left).as_mouse_press(), Some(MouseButton::Left));
+        assert_eq!(Input::Release(left).as_mouse_release(), Some(MouseButton::Left));
+        assert_eq!(Input::move_to(1.0, 2.0).as_move(), Some(Motion::MouseCursor(1.0, 2.0)));
+        assert_eq!(Input::move_to(1.0, 2.0).as_mouse_move(), Some([1.0, 2.0]));
+        assert_eq!(Input::move_by(1.0, 2.0).as_mouse_move(), None);
+        assert_eq!(Input::Text("a".to_string()).as_text(), Some("a"));
+        assert_eq!(Input::Focus(true).as_text(), None);
+    }
+
+    #[test]
+    fn test_conversions() {
+        use std::convert::TryFrom;
+
+        assert_eq!(Button::from(Key::A), Button::Keyboard(Key::A));
+        assert_eq!(Button::from(MouseButton::Left), Button::Mouse(MouseButton::Left));
+        assert_eq!(Button::from(StylusButton::Tip), Button::Stylus(StylusButton::Tip));
+
+        let motion = Motion::MouseCursor(1.0, 2.0);
+        let touch = Touch::new(1, touch::TouchPhase::Began, [1.0, 2.0]);
+        let hover = PenHover::InRange;
+        assert_eq!(Input::from(motion), Input::Move(motion));
+        assert_eq!(Input::from(touch), Input::Touch(touch));
+        assert_eq!(Input::from(hover), Input::PenHover(hover));
+        assert_eq!(Motion::try_from(Input::Move(motion)), Ok(motion));
+        assert_eq!(Touch::try_from(Input::Touch(touch)), Ok(touch));
+        assert_eq!(PenHover::try_from(Input::PenHover(hover)), Ok(hover));
+        assert_eq!(Motion::try_from(Input::Focus(true)), Err(Input::Focus(true)));
+        assert_eq!(Touch::try_from(Input::Move(motion)), Err(Input::Move(motion)));
+        assert_eq!(PenHover::try_from(Input::Touch(touch)), Err(Input::Touch(touch)));
+    }
+
+    #[test]
+    fn test_display() {
+        let south = Button::Gamepad(1, GamepadButton::South);
+        assert_eq!(Button::Keyboard(Key::LShift).to_string(), "key LShift");
+        assert_eq!(Button::Mouse(MouseButton::Left).to_string(), "mouse Left");
+        assert_eq!(Button::Stylus(StylusButton::Eraser).to_string(), "stylus Eraser");
+        assert_eq!(south.to_string(), "gamepad1 South");
+
+        assert_eq!(Motion::MouseCursor(1.5, 2.0).to_string(), "cursor 1.5 2");
+        assert_eq!(Motion::MouseRelative(-1.0, 2.0).to_string(), "relative -1 2");
+        assert_eq!(Motion::MouseScroll(0.0, 1.0).to_string(), "scroll 0 1");
+        assert_eq!(Motion::GamepadAxis(1, GamepadAxis::LeftStickX, 0.5).to_string(),
+                   "gamepad1 LeftStickX 0.5");
+        assert_eq!(Motion::GamepadGyro(1, [1.0, 2.0, 3.0]).to_string(), "gamepad1 gyro 1 2 3");
+        assert_eq!(Motion::GamepadAccel(1, [1.0, 2.0, 3.0]).to_string(), "gamepad1 accel 1 2 3");
+
+        let touch = Touch::new(3, touch::TouchPhase::Moved, [1.0, 2.0]);
+        let battery = |percent| Battery { percent: percent, charging: percent.is_some() };
+        let cases = vec![
+            (Input::Press(Button::Keyboard(Key::LShift)), "press key LShift"),
+            (Input::Release(south), "release gamepad1 South"),
+            (Input::move_to(1.0, 2.0), "move cursor 1 2"),
+            (Input::Text("a\"b".to_string()), "text \"a\\\"b\""),
+            (Input::Resize(800, 600), "resize 800x600"),
+            (Input::Focus(false), "focus false"),
+            (Input::OnScreenKeyboard(true), "on-screen keyboard true"),
+            (Input::Touch(touch), "touch 3 Moved 1 2"),
+            (Input::PenHover(PenHover::InRange), "pen hover in range"),
+            (Input::PenHover(PenHover::Move([1.0, 2.0], None)), "pen hover 1 2"),
+            (Input::PenHover(PenHover::OutOfRange), "pen hover out of range"),
+            (Input::GamepadTouch(1, touch), "gamepad1 touch 3 Moved 1 2"),
+            (Input::GamepadBattery(0, battery(Some(50))), "gamepad0 battery 50% charging"),
+            (Input::GamepadBattery(0, battery(None)), "gamepad0 battery unknown"),
+            (Input::GamepadConnected(2, false), "gamepad2 disconnected"),
+            (Input::DrumHit(0, DrumPad::Red, 0.5), "gamepad0 drum Red 0.5"),
+        ];
+        for (input, line) in cases {
+            assert_eq!(input.to_string(), line);
+            assert_eq!(input.to_ref().to_string(), line);
+        }
+    }
+
+    #[test]
+    fn test_input_ref() {
+        let inputs = vec![
+            Input::Press(Button::Keyboard(Key::A)),
+            Input::Text("hello".to_string()),
+            Input::Move(Motion::MouseCursor(1.0, 2.0)),
+            Input::GamepadBattery(1, Battery { percent: Some(50), charging: true }),
+        ];
+        for input in inputs {
+            let input_ref = input.to_ref();
+            assert_eq!(input_ref, InputRef::from(&input));
+            assert_eq!(input_ref.to_input(), input);
+            assert_eq!(Input::from(input_ref), input);
+        }
+    }
 }
@@ -0,0 +1,83 @@
+//! Logging of input through the `log` crate.
+//!
+//! Enabled with the `log` feature. Records use the target `"input"`,
+//! so they can be filtered like other log records.
+//! Sensitive input in secure mode is never logged.
+
+use log::Level;
+
+use Input;
+use touch::{ PenHover, TouchPhase };
+use secure;
+
+/// The target of input log records.
+pub const TARGET: &'static str = "input";
+
+/// Logs an input at a level, unless it is sensitive.
+pub fn log_input(level: Level, input: &Input) {
+    if secure::is_sensitive(input) { return; }
+    log!(target: TARGET, level, "{}", input);
+}
+
+/// Logs the inputs passing through a stage of a pipeline.
+#[derive(Copy, Clone, Debug)]
+pub struct InputLogger {
+    /// The level of the records.
+    pub level: Level,
+    /// Whether to log motion, which is usually very frequent.
+    ///
+    /// Motion is any `Input::Move`, pen hover moves and moving or
+    /// stationary touches, also of gamepad touchpads.
+    pub motion: bool,
+}
+
+impl InputLogger {
+    /// Creates a new logger logging at debug level without motion.
+    pub fn new() -> InputLogger {
+        InputLogger {
+            level: Level::Debug,
+            motion: false,
+        }
+    }
+
+    /// Logs an input.
+    pub fn handle_input(&self, input: &Input) {
+        if !self.motion && is_motion(input) { return; }
+        log_input(self.level, input);
+    }
+}
+
+fn is_motion(input: &Input) -> bool {
+    match *input {
+        Input::Move(_) | Input::PenHover(PenHover::Move(..)) => true,
+        Input::Touch(touch) | Input::GamepadTouch(_, touch) => match touch.phase {
+            TouchPhase::Moved | TouchPhase::Stationary => true,
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+impl Default for InputLogger {
+    fn default() -> InputLogger { InputLogger::new() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use { Button, Key, Motion };
+    use touch::Touch;
+
+    #[test]
+    fn test_is_motion() {
+        assert!(is_motion(&Input::Move(Motion::MouseCursor(1.0, 2.0))));
+        assert!(is_motion(&Input::Move(Motion::MouseScroll(0.0, 1.0))));
+        assert!(is_motion(&Input::PenHover(PenHover::Move([1.0, 2.0], None))));
+        assert!(is_motion(&Input::Touch(Touch::new(0, TouchPhase::Moved, [1.0, 2.0]))));
+        assert!(is_motion(&Input::GamepadTouch(0, Touch::new(0, TouchPhase::Stationary,
+                                                                [1.0, 2.0]))));
+        assert!(!is_motion(&Input::Touch(Touch::new(0, TouchPhase::Began, [1.0, 2.0]))));
+        assert!(!is_motion(&Input::PenHover(PenHover::InRange)));
+        assert!(!is_motion(&Input::Press(Button::Keyboard(Key::A))));
+    }
+}
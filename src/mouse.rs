@@ -3,9 +3,15 @@
 
 use num::{ FromPrimitive, ToPrimitive };
 
+#[cfg(feature = "quickcheck")]
+use quickcheck::{ Arbitrary, Gen };
+
 /// Represent a mouse button.
+///
+/// More buttons may be added, so matches need a wildcard arm.
 #[derive(Copy, Clone, RustcDecodable, RustcEncodable, PartialEq,
     Eq, Ord, PartialOrd, Hash, Debug)]
+#[non_exhaustive]
 pub enum MouseButton {
     /// Unknown mouse button.
     Unknown,
@@ -25,6 +31,13 @@ pub enum MouseButton {
     Button7,
     /// Mouse button number 8.
     Button8,
+    /// Mouse button above number 8 with its number,
+    /// passed through from the back-end.
+    ///
+    /// Numbers of the named buttons are normalized to them by `from_u64`
+    /// and the wire format, so `Other(3)` comes back as `Middle`.
+    /// Back-ends should only construct this for numbers above 8.
+    Other(u32),
 }
 
 impl FromPrimitive for MouseButton {
@@ -39,6 +52,7 @@ impl FromPrimitive for MouseButton {
             6 => Some(MouseButton::Button6),
             7 => Some(MouseButton::Button7),
             8 => Some(MouseButton::Button8),
+            n if n <= u32::max_value() as u64 => Some(MouseButton::Other(n as u32)),
             _ => Some(MouseButton::Unknown),
         }
     }
@@ -65,7 +79,8 @@ impl ToPrimitive for MouseButton {
             &MouseButton::X2 => Some(5),
             &MouseButton::Button6 => Some(6),
             &MouseButton::Button7 => Some(7),
-            &MouseButton::Button8 => Some(8), 
+            &MouseButton::Button8 => Some(8),
+            &MouseButton::Other(n) => Some(n as u64),
         }
     }
 
@@ -80,6 +95,13 @@ impl ToPrimitive for MouseButton {
     }
 }
 
+#[cfg(feature = "quickcheck")]
+impl Arbitrary for MouseButton {
+    fn arbitrary(g: &mut Gen) -> MouseButton {
+        FromPrimitive::from_u64(u64::arbitrary(g) % 16).unwrap()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -88,10 +110,11 @@ mod tests {
     fn test_mouse_button_primitives() {
         use num::{ FromPrimitive, ToPrimitive };
 
-        for i in 0u64..9 {
+        for i in 0u64..16 {
             let button: MouseButton = FromPrimitive::from_u64(i).unwrap();
             let j = ToPrimitive::to_u64(&button).unwrap();
             assert_eq!(i, j);
         }
+        assert_eq!(<MouseButton as FromPrimitive>::from_u64(12), Some(MouseButton::Other(12)));
     }
 }
@@ -0,0 +1,101 @@
+//! Application defined names of buttons.
+
+use std::collections::HashMap;
+
+use Button;
+
+/// Maps buttons to logical names and back, e.g. "FirePrimary".
+///
+/// Names of gamepad buttons are pad-independent.
+#[derive(Clone, Debug)]
+pub struct ButtonNames {
+    names: HashMap<Button, String>,
+    buttons: HashMap<String, Button>,
+}
+
+impl ButtonNames {
+    /// Creates a new registry without names.
+    pub fn new() -> ButtonNames {
+        ButtonNames {
+            names: HashMap::new(),
+            buttons: HashMap::new(),
+        }
+    }
+
+    /// Names a button.
+    ///
+    /// Replaces the old name of the button and the button with the name.
+    pub fn set_name(&mut self, button: Button, name: &str) {
+        let button = button.any_gamepad();
+        self.remove(button);
+        if let Some(old) = self.buttons.remove(name) {
+            self.names.remove(&old);
+        }
+        self.names.insert(button, name.to_string());
+        self.buttons.insert(name.to_string(), button);
+    }
+
+    /// Removes the name of a button.
+    pub fn remove(&mut self, button: Button) -> Option<String> {
+        let name = self.names.remove(&button.any_gamepad());
+        if let Some(ref name) = name {
+            self.buttons.remove(name);
+        }
+        name
+    }
+
+    /// Returns the name of a button.
+    pub fn name(&self, button: Button) -> Option<&str> {
+        self.names.get(&button.any_gamepad()).map(|name| &name[..])
+    }
+
+    /// Returns the button with a name.
+    pub fn button(&self, name: &str) -> Option<Button> {
+        self.buttons.get(name).cloned()
+    }
+
+    /// Returns the name of a button, or its prompt name if it has none.
+    pub fn display_name(&self, button: Button) -> String {
+        match self.name(button) {
+            Some(name) => name.to_string(),
+            None => button.prompt_name(),
+        }
+    }
+}
+
+impl Default for ButtonNames {
+    fn default() -> ButtonNames { ButtonNames::new() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use { GamepadButton, Key };
+
+    #[test]
+    fn test_names() {
+        let a = Button::Keyboard(Key::A);
+        let b = Button::Keyboard(Key::B);
+        let mut names = ButtonNames::new();
+        names.set_name(a, "FirePrimary");
+        assert_eq!(names.name(a), Some("FirePrimary"));
+        assert_eq!(names.button("FirePrimary"), Some(a));
+        assert_eq!(names.display_name(b), "kbd_b");
+
+        names.set_name(b, "FirePrimary");
+        assert_eq!(names.name(a), None);
+        assert_eq!(names.button("FirePrimary"), Some(b));
+        names.set_name(b, "Jump");
+        assert_eq!(names.button("FirePrimary"), None);
+        assert_eq!(names.remove(b), Some("Jump".to_string()));
+        assert_eq!(names.button("Jump"), None);
+    }
+
+    #[test]
+    fn test_gamepads() {
+        let mut names = ButtonNames::new();
+        names.set_name(Button::Gamepad(2, GamepadButton::South), "Jump");
+        assert_eq!(names.name(Button::Gamepad(5, GamepadButton::South)), Some("Jump"));
+        assert_eq!(names.button("Jump"), Some(Button::Gamepad(0, GamepadButton::South)));
+    }
+}
@@ -0,0 +1,85 @@
+//! Recycling of heap storage used by inputs.
+//!
+//! `Input::Text` is the only input owning heap data.
+//! A back-end can create text inputs from a pool and have the
+//! application return them when handled, avoiding an allocation
+//! per text input at high rates.
+
+use Input;
+
+/// A pool of text buffers for `Input::Text`.
+#[derive(Clone, Debug)]
+pub struct TextPool {
+    /// The maximum number of buffers kept for reuse.
+    pub capacity: usize,
+    buffers: Vec<String>,
+}
+
+impl TextPool {
+    /// Creates a new empty pool keeping at most `capacity` buffers.
+    pub fn new(capacity: usize) -> TextPool {
+        TextPool {
+            capacity: capacity,
+            buffers: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Creates a text input, reusing a pooled buffer if available.
+    pub fn text(&mut self, text: &str) -> Input {
+        let mut buffer = self.buffers.pop().unwrap_or_else(String::new);
+        buffer.push_str(text);
+        Input::Text(buffer)
+    }
+
+    /// Returns the storage of a handled input to the pool.
+    pub fn recycle(&mut self, input: Input) {
+        if let Input::Text(mut buffer) = input {
+            if self.buffers.len() < self.capacity {
+                buffer.clear();
+                self.buffers.push(buffer);
+            }
+        }
+    }
+
+    /// Returns the number of buffers available for reuse.
+    pub fn len(&self) -> usize {
+        self.buffers.len()
+    }
+
+    /// Returns `true` if no buffers are available for reuse.
+    pub fn is_empty(&self) -> bool {
+        self.buffers.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use { Button, Key };
+
+    #[test]
+    fn test_reuse() {
+        let mut pool = TextPool::new(1);
+        assert!(pool.is_empty());
+        let input = pool.text("hello");
+        assert_eq!(input, Input::Text("hello".to_string()));
+        let ptr = input.as_text().unwrap().as_ptr();
+        pool.recycle(input);
+        assert_eq!(pool.len(), 1);
+
+        let input = pool.text("hi");
+        assert_eq!(input, Input::Text("hi".to_string()));
+        assert_eq!(input.as_text().unwrap().as_ptr(), ptr);
+        assert!(pool.is_empty());
+    }
+
+    #[test]
+    fn test_capacity() {
+        let mut pool = TextPool::new(1);
+        pool.recycle(Input::Text("a".to_string()));
+        pool.recycle(Input::Text("b".to_string()));
+        pool.recycle(Input::Press(Button::Keyboard(Key::A)));
+        assert_eq!(pool.len(), 1);
+        assert_eq!(pool.text(""), Input::Text(String::new()));
+    }
+}
@@ -0,0 +1,16 @@
+//! Re-exports the most commonly used types and traits.
+//!
+//! ```
+//! use input::prelude::*;
+//! ```
+
+pub use { Button, Input, InputRef, Motion };
+#[cfg(feature = "mapping")]
+pub use action::{ ActionEvent, ActionMap };
+pub use clock::Clock;
+pub use generic::GenericEvent;
+pub use gamepad::{ GamepadAxis, GamepadButton };
+pub use keyboard::Key;
+pub use mouse::MouseButton;
+pub use state::InputState;
+pub use touch::{ PenHover, StylusButton, Touch, TouchDevice, TouchPhase };
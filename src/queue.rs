@@ -0,0 +1,263 @@
+//! Queues of inputs supporting batch delivery and broadcasting.
+
+use std::collections::VecDeque;
+
+use { Input, Touch };
+use touch::TouchPhase;
+
+/// The priority of an input, deciding what a full queue drops first.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum Priority {
+    /// Motion, which is frequent and superseded by later motion.
+    Motion,
+    /// Regular input, e.g. presses and text.
+    Regular,
+    /// Releases and ending touches, kept longer than presses
+    /// so dropping inputs never leaves a button held.
+    Release,
+    /// Changes of the window or devices, e.g. focus or disconnects.
+    System,
+}
+
+impl Priority {
+    /// Returns the priority of an input.
+    pub fn of(input: &Input) -> Priority {
+        match *input {
+            Input::Move(_) | Input::PenHover(_) => Priority::Motion,
+            Input::Touch(touch) | Input::GamepadTouch(_, touch) => touch_priority(touch),
+            Input::Resize(..) | Input::Focus(_) | Input::OnScreenKeyboard(_) |
+            Input::GamepadBattery(..) | Input::GamepadConnected(..) => Priority::System,
+            Input::Release(_) => Priority::Release,
+            Input::Press(_) | Input::Text(_) | Input::DrumHit(..) => Priority::Regular,
+        }
+    }
+}
+
+fn touch_priority(touch: Touch) -> Priority {
+    match touch.phase {
+        TouchPhase::Moved | TouchPhase::Stationary => Priority::Motion,
+        TouchPhase::Began => Priority::Regular,
+        TouchPhase::Ended | TouchPhase::Cancelled => Priority::Release,
+    }
+}
+
+/// A first-in first-out queue of inputs.
+///
+/// Back-ends can push whole batches of inputs at once,
+/// and consumers can drain everything queued in one call.
+///
+/// When a capacity is set and the queue is full, the oldest input
+/// of the lowest priority is dropped, so motion spam is dropped
+/// before presses, presses before releases, and releases before
+/// focus changes or disconnects.
+/// An input is dropped itself if everything queued has higher priority.
+#[derive(Clone, Debug)]
+pub struct InputQueue {
+    /// The maximum number of queued inputs, or `None` for no limit.
+    pub capacity: Option<usize>,
+    inputs: VecDeque<Input>,
+    dropped: usize,
+}
+
+impl InputQueue {
+    /// Creates a new empty queue without a capacity.
+    pub fn new() -> InputQueue {
+        InputQueue {
+            capacity: None,
+            inputs: VecDeque::new(),
+            dropped: 0,
+        }
+    }
+
+    /// Returns the number of inputs dropped because the queue was full.
+    pub fn dropped(&self) -> usize {
+        self.dropped
+    }
+
+    /// Pushes an input to the back of the queue.
+    ///
+    /// With the `log` feature, queued inputs are logged at trace level.
+    pub fn push(&mut self, input: Input) {
+        #[cfg(feature = "log")]
+        ::logging::log_input(::log::Level::Trace, &input);
+        if let Some(capacity) = self.capacity {
+            while self.inputs.len() >= capacity {
+                let priority = Priority::of(&input);
+                let lowest = self.inputs.iter().enumerate()
+                    .map(|(i, queued)| (Priority::of(queued), i))
+                    .min();
+                self.dropped += 1;
+                match lowest {
+                    Some((p, i)) if p <= priority => { self.inputs.remove(i); }
+                    _ => return,
+                }
+            }
+        }
+        self.inputs.push_back(input);
+    }
+
+    /// Pushes a batch of inputs to the back of the queue.
+    pub fn push_batch(&mut self, inputs: &[Input]) {
+        for input in inputs {
+            self.push(input.clone());
+        }
+    }
+
+    /// Pops the input at the front of the queue.
+    pub fn pop(&mut self) -> Option<Input> {
+        self.inputs.pop_front()
+    }
+
+    /// Moves all queued inputs to the end of `out`.
+    pub fn drain_batch(&mut self, out: &mut Vec<Input>) {
+        out.extend(self.inputs.drain(..));
+    }
+
+    /// Returns the number of queued inputs.
+    pub fn len(&self) -> usize {
+        self.inputs.len()
+    }
+
+    /// Returns `true` if no inputs are queued.
+    pub fn is_empty(&self) -> bool {
+        self.inputs.is_empty()
+    }
+}
+
+impl Default for InputQueue {
+    fn default() -> InputQueue { InputQueue::new() }
+}
+
+/// Identifies a consumer of a broadcast.
+///
+/// Ids of removed consumers stay invalid when their slot is reused.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct ConsumerId {
+    index: usize,
+    generation: u32,
+}
+
+/// Broadcasts inputs to several consumers, e.g. game logic,
+/// a recorder and a network sender.
+///
+/// Each consumer has its own queue, so consuming inputs
+/// does not affect other consumers.
+#[derive(Clone, Debug)]
+pub struct Broadcast {
+    // The generation of each slot and its consumer's queue.
+    consumers: Vec<(u32, Option<InputQueue>)>,
+}
+
+impl Broadcast {
+    /// Creates a new broadcast without consumers.
+    pub fn new() -> Broadcast {
+        Broadcast { consumers: vec![] }
+    }
+
+    /// Adds a consumer receiving the inputs pushed from now on.
+    pub fn subscribe(&mut self) -> ConsumerId {
+        let index = match self.consumers.iter().position(|c| c.1.is_none()) {
+            Some(i) => i,
+            None => {
+                self.consumers.push((0, None));
+                self.consumers.len() - 1
+            }
+        };
+        let consumer = &mut self.consumers[index];
+        consumer.1 = Some(InputQueue::new());
+        ConsumerId { index: index, generation: consumer.0 }
+    }
+
+    /// Removes a consumer, dropping its queued inputs.
+    pub fn unsubscribe(&mut self, id: ConsumerId) {
+        if self.queue(id).is_some() {
+            let consumer = &mut self.consumers[id.index];
+            consumer.0 = consumer.0.wrapping_add(1);
+            consumer.1 = None;
+        }
+    }
+
+    /// Pushes an input to the queues of all consumers.
+    pub fn push(&mut self, input: &Input) {
+        for queue in self.consumers.iter_mut().filter_map(|c| c.1.as_mut()) {
+            queue.push(input.clone());
+        }
+    }
+
+    /// Returns the queue of a consumer,
+    /// or `None` if the consumer was removed.
+    pub fn queue(&mut self, id: ConsumerId) -> Option<&mut InputQueue> {
+        match self.consumers.get_mut(id.index) {
+            Some(&mut (generation, ref mut queue)) if generation == id.generation =>
+                queue.as_mut(),
+            _ => None,
+        }
+    }
+}
+
+impl Default for Broadcast {
+    fn default() -> Broadcast { Broadcast::new() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use { Input, Key, Touch };
+    use touch::TouchPhase;
+
+    #[test]
+    fn test_overflow() {
+        let mut queue = InputQueue::new();
+        queue.capacity = Some(3);
+        queue.push(Input::move_to(0.0, 0.0));
+        queue.push(Input::key_press(Key::A));
+        queue.push(Input::move_to(1.0, 1.0));
+        queue.push(Input::Focus(false));
+        queue.push(Input::key_release(Key::A));
+        queue.push(Input::move_to(2.0, 2.0));
+        assert_eq!(queue.dropped(), 3);
+        let mut inputs = vec![];
+        queue.drain_batch(&mut inputs);
+        assert_eq!(inputs, vec![Input::key_press(Key::A), Input::Focus(false),
+                                Input::key_release(Key::A)]);
+
+        // Presses are dropped before releases, so no key stays held.
+        queue.push(Input::key_release(Key::A));
+        queue.push(Input::key_press(Key::B));
+        queue.push(Input::key_press(Key::A));
+        queue.push(Input::key_release(Key::B));
+        queue.drain_batch(&mut inputs);
+        assert_eq!(&inputs[3..], &[Input::key_release(Key::A), Input::key_press(Key::A),
+                                   Input::key_release(Key::B)]);
+
+        let touch = |phase| Input::Touch(Touch::new(1, phase, [0.0, 0.0]));
+        queue.push(touch(TouchPhase::Ended));
+        queue.push(touch(TouchPhase::Began));
+        queue.push(touch(TouchPhase::Began));
+        queue.push(touch(TouchPhase::Cancelled));
+        inputs.clear();
+        queue.drain_batch(&mut inputs);
+        assert_eq!(inputs, vec![touch(TouchPhase::Ended), touch(TouchPhase::Began),
+                                touch(TouchPhase::Cancelled)]);
+    }
+
+    #[test]
+    fn test_broadcast() {
+        let mut broadcast = Broadcast::new();
+        let a = broadcast.subscribe();
+        broadcast.push(&Input::Focus(true));
+        let b = broadcast.subscribe();
+        broadcast.push(&Input::Focus(false));
+        assert_eq!(broadcast.queue(a).unwrap().len(), 2);
+        assert_eq!(broadcast.queue(b).unwrap().pop(), Some(Input::Focus(false)));
+        assert_eq!(broadcast.queue(a).unwrap().len(), 2);
+
+        broadcast.unsubscribe(a);
+        let c = broadcast.subscribe();
+        broadcast.push(&Input::Focus(true));
+        // The slot of `a` is reused, but `a` stays invalid.
+        assert!(broadcast.queue(a).is_none());
+        broadcast.unsubscribe(a);
+        assert_eq!(broadcast.queue(c).unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,425 @@
+//! Recording and playback of input.
+
+use std::collections::BTreeMap;
+
+use rustc_serialize::{ self, Decodable };
+
+use Input;
+use secure;
+use wire::{ self, Decoder, Encoder, WireError };
+
+/// The version of the replay format written by `Macro::to_replay`.
+///
+/// Version 1 stores the inputs in the wire format.
+/// Version 2 adds metadata.
+pub const REPLAY_VERSION: u8 = 2;
+
+const REPLAY_MAGIC: &'static [u8] = b"PRP";
+
+/// A named sequence of recorded inputs.
+#[derive(Clone, RustcEncodable, PartialEq, Debug)]
+pub struct Macro {
+    /// The name of the macro.
+    pub name: String,
+    /// Inputs with the delay in seconds since the previous input.
+    pub events: Vec<(f64, Input)>,
+    /// Information needed to interpret the recording later,
+    /// e.g. `"app_version"`, `"keyboard_layout"`, `"screen_size"`
+    /// or descriptions of the devices used.
+    pub metadata: BTreeMap<String, String>,
+}
+
+impl Macro {
+    /// Creates a new empty macro.
+    pub fn new(name: &str) -> Macro {
+        Macro {
+            name: name.to_string(),
+            events: vec![],
+            metadata: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the total duration of the macro in seconds.
+    pub fn duration(&self) -> f64 {
+        self.events.iter().map(|&(delay, _)| delay).sum()
+    }
+
+    /// Encodes the macro as a replay file.
+    ///
+    /// Delays are rounded to microseconds.
+    pub fn to_replay(&self) -> Vec<u8> {
+        let mut buf = vec![];
+        buf.extend(REPLAY_MAGIC);
+        buf.push(REPLAY_VERSION);
+        write_string(&mut buf, &self.name);
+        wire::write_varint(&mut buf, self.metadata.len() as u64);
+        for (key, value) in &self.metadata {
+            write_string(&mut buf, key);
+            write_string(&mut buf, value);
+        }
+        wire::write_header(&mut buf);
+        let mut encoder = Encoder::new();
+        let mut time = 0.0;
+        for &(delay, ref input) in &self.events {
+            time += delay;
+            encoder.encode(time, input, &mut buf);
+        }
+        buf
+    }
+
+    /// Decodes a replay file written by this or an older version.
+    pub fn from_replay(bytes: &[u8]) -> Result<Macro, WireError> {
+        match Macro::from_replay_partial(bytes) {
+            (recorded, None) => Ok(recorded),
+            (_, Some(err)) => Err(err),
+        }
+    }
+
+    /// Decodes a replay file up to the first error, e.g. an input
+    /// that is unknown to this version of the crate.
+    ///
+    /// Returns the decoded part of the macro and the error, if any.
+    pub fn from_replay_partial(bytes: &[u8]) -> (Macro, Option<WireError>) {
+        let mut recorded = Macro::new("");
+        if bytes.len() < REPLAY_MAGIC.len() + 1 {
+            return (recorded, Some(WireError::Truncated));
+        }
+        if &bytes[..REPLAY_MAGIC.len()] != REPLAY_MAGIC {
+            return (recorded, Some(WireError::BadMagic));
+        }
+        let mut pos = REPLAY_MAGIC.len() + 1;
+        // Older versions are migrated here when the format changes.
+        let err = match bytes[REPLAY_MAGIC.len()] {
+            1 => read_replay(bytes, &mut pos, &mut recorded, false).err(),
+            2 => read_replay(bytes, &mut pos, &mut recorded, true).err(),
+            version => Some(WireError::UnknownVersion(version)),
+        };
+        (recorded, err)
+    }
+}
+
+// Decoded by hand, so macros encoded before metadata was added still load.
+impl Decodable for Macro {
+    fn decode<D: rustc_serialize::Decoder>(d: &mut D) -> Result<Macro, D::Error> {
+        d.read_struct("Macro", 3, |d| {
+            Ok(Macro {
+                name: d.read_struct_field("name", 0, Decodable::decode)?,
+                events: d.read_struct_field("events", 1, Decodable::decode)?,
+                metadata: d.read_struct_field("metadata", 2,
+                    |d| Option::<BTreeMap<String, String>>::decode(d))?.unwrap_or_default(),
+            })
+        })
+    }
+}
+
+fn write_string(buf: &mut Vec<u8>, s: &str) {
+    wire::write_varint(buf, s.len() as u64);
+    buf.extend(s.as_bytes());
+}
+
+fn read_string(bytes: &[u8], pos: &mut usize) -> Result<String, WireError> {
+    let len = wire::read_varint(bytes, pos)? as usize;
+    if bytes.len() - *pos < len { return Err(WireError::Truncated); }
+    let s = String::from_utf8(bytes[*pos..*pos + len].to_vec())
+        .map_err(|_| WireError::Invalid)?;
+    *pos += len;
+    Ok(s)
+}
+
+// Version 1 replays have no metadata.
+fn read_replay(bytes: &[u8], pos: &mut usize, recorded: &mut Macro, metadata: bool)
+    -> Result<(), WireError>
+{
+    recorded.name = read_string(bytes, pos)?;
+    if metadata {
+        for _ in 0..wire::read_varint(bytes, pos)? {
+            let key = read_string(bytes, pos)?;
+            let value = read_string(bytes, pos)?;
+            recorded.metadata.insert(key, value);
+        }
+    }
+    *pos += wire::read_header(&bytes[*pos..])?.1;
+    let mut decoder = Decoder::new();
+    let mut last = 0.0;
+    while *pos < bytes.len() {
+        let (time, input, n) = decoder.decode(&bytes[*pos..])?;
+        recorded.events.push((time - last, input));
+        last = time;
+        *pos += n;
+    }
+    Ok(())
+}
+
+/// Records inputs into a macro.
+#[derive(Clone, Debug)]
+pub struct Recorder {
+    /// The maximum number of inputs recorded into one macro.
+    pub capacity: usize,
+    recording: Option<Macro>,
+    delay: f64,
+}
+
+impl Recorder {
+    /// Creates a new recorder holding at most `capacity` inputs per macro.
+    pub fn new(capacity: usize) -> Recorder {
+        Recorder {
+            capacity: capacity,
+            recording: None,
+            delay: 0.0,
+        }
+    }
+
+    /// Starts recording a new macro, discarding any unfinished one.
+    pub fn start(&mut self, name: &str) {
+        self.recording = Some(Macro::new(name));
+        self.delay = 0.0;
+    }
+
+    /// Stops recording and returns the recorded macro.
+    pub fn stop(&mut self) -> Option<Macro> {
+        self.recording.take()
+    }
+
+    /// Returns `true` if a macro is being recorded.
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    /// Records an input.
+    ///
+    /// Inputs beyond the capacity and sensitive inputs in secure mode
+    /// are ignored.
+    pub fn handle_input(&mut self, input: &Input) {
+        self.handle(input, secure::is_secure_mode());
+    }
+
+    fn handle(&mut self, input: &Input, secure_mode: bool) {
+        if secure::sensitive(input, secure_mode) { return; }
+        if let Some(ref mut recording) = self.recording {
+            if recording.events.len() < self.capacity {
+                recording.events.push((self.delay, input.clone()));
+                self.delay = 0.0;
+            }
+        }
+    }
+
+    /// Advances time.
+    pub fn update(&mut self, dt: f64) {
+        if self.recording.is_some() {
+            self.delay += dt;
+        }
+    }
+}
+
+/// Plays back a macro with its original timing.
+#[derive(Clone, Debug)]
+pub struct Player {
+    recorded: Macro,
+    index: usize,
+    time: f64,
+}
+
+impl Player {
+    /// Creates a new player starting at the beginning of the macro.
+    pub fn new(recorded: Macro) -> Player {
+        Player {
+            recorded: recorded,
+            index: 0,
+            time: 0.0,
+        }
+    }
+
+    /// Returns the macro being played.
+    pub fn recorded(&self) -> &Macro {
+        &self.recorded
+    }
+
+    /// Returns `true` if all inputs have been played.
+    pub fn is_finished(&self) -> bool {
+        self.index >= self.recorded.events.len()
+    }
+
+    /// Advances time, returning the inputs that are due.
+    pub fn update(&mut self, dt: f64) -> Vec<Input> {
+        self.time += dt;
+        let mut out = vec![];
+        while let Some(&(delay, ref input)) = self.recorded.events.get(self.index) {
+            if delay > self.time { break; }
+            self.time -= delay;
+            self.index += 1;
+            out.push(input.clone());
+        }
+        out
+    }
+}
+
+/// Inputs recorded per frame instead of by time.
+///
+/// Replaying frame by frame gives the same inputs in the same order
+/// on every machine, independent of frame rate.
+#[derive(Clone, RustcDecodable, RustcEncodable, PartialEq, Debug)]
+pub struct FrameRecording {
+    frames: Vec<Vec<Input>>,
+}
+
+impl FrameRecording {
+    /// Creates a new recording starting at frame 0.
+    pub fn new() -> FrameRecording {
+        FrameRecording { frames: vec![vec![]] }
+    }
+
+    /// Records an input in the current frame.
+    ///
+    /// Sensitive inputs in secure mode are ignored.
+    pub fn handle_input(&mut self, input: &Input) {
+        self.handle(input, secure::is_secure_mode());
+    }
+
+    fn handle(&mut self, input: &Input, secure_mode: bool) {
+        if secure::sensitive(input, secure_mode) { return; }
+        if self.frames.is_empty() { self.frames.push(vec![]); }
+        let last = self.frames.len() - 1;
+        self.frames[last].push(input.clone());
+    }
+
+    /// Starts recording the next frame.
+    pub fn next_frame(&mut self) {
+        self.frames.push(vec![]);
+    }
+
+    /// Returns the index of the frame being recorded.
+    pub fn current_frame(&self) -> usize {
+        self.frames.len().saturating_sub(1)
+    }
+
+    /// Returns the inputs of each frame in the order they were received.
+    pub fn frames(&self) -> &[Vec<Input>] {
+        &self.frames
+    }
+
+    /// Returns the inputs of a frame.
+    ///
+    /// Frames outside the recording have no inputs.
+    pub fn frame(&self, index: usize) -> &[Input] {
+        match self.frames.get(index) {
+            Some(inputs) => inputs,
+            None => &[],
+        }
+    }
+}
+
+impl Default for FrameRecording {
+    fn default() -> FrameRecording { FrameRecording::new() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use { Button, Input, Key, MouseButton };
+
+    #[test]
+    fn test_record_and_play() {
+        let a = Button::Keyboard(Key::A);
+        let mut recorder = Recorder::new(2);
+        recorder.handle_input(&Input::Press(a));
+        recorder.start("a");
+        recorder.update(0.5);
+        recorder.handle_input(&Input::Press(a));
+        recorder.update(0.25);
+        recorder.handle_input(&Input::Release(a));
+        recorder.handle_input(&Input::Press(a));
+        let recorded = recorder.stop().unwrap();
+        assert_eq!(recorded.events.len(), 2);
+        assert_eq!(recorded.duration(), 0.75);
+
+        let mut player = Player::new(recorded);
+        assert!(player.update(0.25).is_empty());
+        assert_eq!(player.update(0.25), vec![Input::Press(a)]);
+        assert_eq!(player.update(0.25), vec![Input::Release(a)]);
+        assert!(player.is_finished());
+    }
+
+    #[test]
+    fn test_frame_recording() {
+        let a = Button::Keyboard(Key::A);
+        let mut recording = FrameRecording::new();
+        assert_eq!(recording.current_frame(), 0);
+        recording.handle_input(&Input::Press(a));
+        recording.next_frame();
+        recording.next_frame();
+        recording.handle_input(&Input::Release(a));
+        assert_eq!(recording.current_frame(), 2);
+        assert_eq!(recording.frame(0), &[Input::Press(a)]);
+        assert!(recording.frame(1).is_empty());
+        assert_eq!(recording.frame(2), &[Input::Release(a)]);
+        assert!(recording.frame(3).is_empty());
+        assert_eq!(recording.frames().len(), 3);
+    }
+
+    #[test]
+    fn test_secure_mode() {
+        let a = Button::Keyboard(Key::A);
+        let left = Button::Mouse(MouseButton::Left);
+        let mut recorder = Recorder::new(8);
+        recorder.start("login");
+        recorder.handle(&Input::Press(a), true);
+        recorder.update(0.5);
+        recorder.handle(&Input::Text("a".to_string()), true);
+        recorder.handle(&Input::Press(left), true);
+        let recorded = recorder.stop().unwrap();
+        assert_eq!(recorded.events, vec![(0.5, Input::Press(left))]);
+
+        let mut recording = FrameRecording::new();
+        recording.handle(&Input::Press(a), true);
+        recording.handle(&Input::Press(left), true);
+        recording.handle(&Input::Release(a), false);
+        assert_eq!(recording.frame(0), &[Input::Press(left), Input::Release(a)]);
+    }
+
+    #[test]
+    fn test_empty_frame_recording() {
+        let mut recording = FrameRecording { frames: vec![] };
+        assert_eq!(recording.current_frame(), 0);
+        assert!(recording.frame(0).is_empty());
+        recording.handle_input(&Input::Focus(true));
+        assert_eq!(recording.frame(0), &[Input::Focus(true)]);
+    }
+
+    #[test]
+    fn test_replay() {
+        let a = Button::Keyboard(Key::A);
+        let mut recorded = Macro::new("a");
+        recorded.events.push((0.5, Input::Press(a)));
+        recorded.events.push((0.25, Input::Release(a)));
+        recorded.metadata.insert("screen_size".to_string(), "1920x1080".to_string());
+        let replay = recorded.to_replay();
+        assert_eq!(Macro::from_replay(&replay), Ok(recorded));
+
+        let (partial, err) = Macro::from_replay_partial(&replay[..replay.len() - 1]);
+        assert_eq!(partial.events, vec![(0.5, Input::Press(a))]);
+        assert_eq!(err, Some(WireError::Truncated));
+    }
+
+    #[test]
+    fn test_replay_version_1() {
+        let replay = [
+            b'P', b'R', b'P', 1,
+            1, b'a',
+            b'P', b'I', b'N', 1,
+            // 0.5 seconds, focus gained.
+            0xa0, 0xc2, 0x1e, 5, 1,
+        ];
+        let recorded = Macro::from_replay(&replay).unwrap();
+        assert_eq!(recorded.name, "a");
+        assert_eq!(recorded.events, vec![(0.5, Input::Focus(true))]);
+        assert!(recorded.metadata.is_empty());
+    }
+
+    #[test]
+    fn test_decode_without_metadata() {
+        use rustc_serialize::json;
+
+        let recorded: Macro = json::decode(r#"{"name":"a","events":[]}"#).unwrap();
+        assert_eq!(recorded, Macro::new("a"));
+    }
+}
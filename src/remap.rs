@@ -0,0 +1,168 @@
+//! Remapping of gamepad buttons and axes.
+//!
+//! Profiles fix the mapping of nonstandard gamepads to the standard
+//! layout, and can be loaded, edited and switched at runtime.
+
+use std::collections::HashMap;
+
+use { Button, GamepadAxis, GamepadButton, Input, Motion };
+
+/// Overrides from reported to standard gamepad buttons and axes.
+#[derive(Clone, RustcDecodable, RustcEncodable, PartialEq, Debug)]
+pub struct RemapProfile {
+    /// The name of the profile.
+    pub name: String,
+    /// Buttons reported by the gamepad and the buttons they map to.
+    pub buttons: HashMap<GamepadButton, GamepadButton>,
+    /// Axes reported by the gamepad and the axes they map to.
+    pub axes: HashMap<GamepadAxis, GamepadAxis>,
+}
+
+impl RemapProfile {
+    /// Creates a new profile without overrides.
+    pub fn new(name: &str) -> RemapProfile {
+        RemapProfile {
+            name: name.to_string(),
+            buttons: HashMap::new(),
+            axes: HashMap::new(),
+        }
+    }
+
+    /// Remaps a button.
+    pub fn button(&self, button: GamepadButton) -> GamepadButton {
+        self.buttons.get(&button).cloned().unwrap_or(button)
+    }
+
+    /// Remaps an axis.
+    pub fn axis(&self, axis: GamepadAxis) -> GamepadAxis {
+        self.axes.get(&axis).cloned().unwrap_or(axis)
+    }
+}
+
+/// Applies remap profiles to the inputs of gamepads.
+#[derive(Clone, Debug)]
+pub struct Remapper {
+    profiles: HashMap<String, RemapProfile>,
+    active: HashMap<u32, String>,
+    // Held buttons and the buttons their presses were mapped to.
+    held: HashMap<(u32, GamepadButton), GamepadButton>,
+}
+
+impl Remapper {
+    /// Creates a new remapper without profiles.
+    pub fn new() -> Remapper {
+        Remapper {
+            profiles: HashMap::new(),
+            active: HashMap::new(),
+            held: HashMap::new(),
+        }
+    }
+
+    /// Adds a profile, replacing a profile with the same name.
+    pub fn add_profile(&mut self, profile: RemapProfile) {
+        self.profiles.insert(profile.name.clone(), profile);
+    }
+
+    /// Removes a profile, which stops applying to its gamepads.
+    pub fn remove_profile(&mut self, name: &str) -> Option<RemapProfile> {
+        self.active.retain(|_, active| active != name);
+        self.profiles.remove(name)
+    }
+
+    /// Returns a profile for editing.
+    ///
+    /// Changes apply to the following inputs.
+    pub fn profile_mut(&mut self, name: &str) -> Option<&mut RemapProfile> {
+        self.profiles.get_mut(name)
+    }
+
+    /// Applies a profile to a gamepad, or stops remapping with `None`.
+    ///
+    /// Returns `false` if there is no profile with the name.
+    pub fn set_profile(&mut self, id: u32, name: Option<&str>) -> bool {
+        match name {
+            Some(name) => {
+                if !self.profiles.contains_key(name) { return false; }
+                self.active.insert(id, name.to_string());
+            }
+            None => { self.active.remove(&id); }
+        }
+        true
+    }
+
+    /// Returns the profile applied to a gamepad.
+    pub fn profile(&self, id: u32) -> Option<&RemapProfile> {
+        self.active.get(&id).and_then(|name| self.profiles.get(name))
+    }
+
+    /// Remaps an input.
+    ///
+    /// Releases map to the same button as their press,
+    /// even if the profile changed while the button was held.
+    pub fn handle_input(&mut self, input: &Input) -> Input {
+        match *input {
+            Input::Press(Button::Gamepad(id, button)) => {
+                let mapped = match self.profile(id) {
+                    Some(profile) => profile.button(button),
+                    None => button,
+                };
+                self.held.insert((id, button), mapped);
+                Input::Press(Button::Gamepad(id, mapped))
+            }
+            Input::Release(Button::Gamepad(id, button)) => {
+                let mapped = match self.held.remove(&(id, button)) {
+                    Some(mapped) => mapped,
+                    None => match self.profile(id) {
+                        Some(profile) => profile.button(button),
+                        None => button,
+                    },
+                };
+                Input::Release(Button::Gamepad(id, mapped))
+            }
+            Input::GamepadConnected(id, false) => {
+                self.held.retain(|&(held_id, _), _| held_id != id);
+                input.clone()
+            }
+            Input::Move(Motion::GamepadAxis(id, axis, value)) => match self.profile(id) {
+                Some(profile) => Input::Move(Motion::GamepadAxis(id, profile.axis(axis), value)),
+                None => input.clone(),
+            },
+            ref x => x.clone(),
+        }
+    }
+}
+
+impl Default for Remapper {
+    fn default() -> Remapper { Remapper::new() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use { Button, GamepadAxis, GamepadButton, Input, Motion };
+
+    #[test]
+    fn test_remap() {
+        let mut profile = RemapProfile::new("swap");
+        profile.buttons.insert(GamepadButton::South, GamepadButton::East);
+        profile.axes.insert(GamepadAxis::LeftStickX, GamepadAxis::RightStickX);
+        let mut remapper = Remapper::new();
+        remapper.add_profile(profile);
+        assert!(!remapper.set_profile(1, Some("nope")));
+        assert!(remapper.set_profile(1, Some("swap")));
+
+        let south = |id| Button::Gamepad(id, GamepadButton::South);
+        assert_eq!(remapper.handle_input(&Input::Press(south(1))),
+                   Input::Press(Button::Gamepad(1, GamepadButton::East)));
+        assert_eq!(remapper.handle_input(&Input::Press(south(2))), Input::Press(south(2)));
+        assert_eq!(remapper.handle_input(
+                       &Input::Move(Motion::GamepadAxis(1, GamepadAxis::LeftStickX, 0.5))),
+                   Input::Move(Motion::GamepadAxis(1, GamepadAxis::RightStickX, 0.5)));
+
+        // The profile changed while South was held.
+        remapper.set_profile(1, None);
+        assert_eq!(remapper.handle_input(&Input::Release(south(1))),
+                   Input::Release(Button::Gamepad(1, GamepadButton::East)));
+        assert_eq!(remapper.handle_input(&Input::Release(south(1))), Input::Release(south(1)));
+    }
+}
@@ -0,0 +1,213 @@
+//! Streaming of input to remote machines.
+//!
+//! Inputs are sent in packets of the wire format. Each packet starts
+//! with a header and times relative to the start of the stream,
+//! so packets can be decoded even if earlier packets were lost.
+
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+use Input;
+use wire::{ self, Decoder, Encoder, WireError };
+
+/// Sends and receives packets, e.g. over UDP or TCP.
+pub trait Transport {
+    /// Sends a packet.
+    fn send(&mut self, packet: &[u8]) -> io::Result<()>;
+    /// Receives a packet, or `None` if no packet is available.
+    fn receive(&mut self) -> io::Result<Option<Vec<u8>>>;
+}
+
+/// An error receiving input.
+#[derive(Debug)]
+pub enum RemoteError {
+    /// The transport failed.
+    Io(io::Error),
+    /// A packet could not be decoded.
+    Wire(WireError),
+}
+
+impl fmt::Display for RemoteError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            RemoteError::Io(ref err) => write!(f, "{}", err),
+            RemoteError::Wire(ref err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl Error for RemoteError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            RemoteError::Io(ref err) => Some(err),
+            RemoteError::Wire(ref err) => Some(err),
+        }
+    }
+}
+
+impl From<io::Error> for RemoteError {
+    fn from(err: io::Error) -> RemoteError { RemoteError::Io(err) }
+}
+
+impl From<WireError> for RemoteError {
+    fn from(err: WireError) -> RemoteError { RemoteError::Wire(err) }
+}
+
+/// Collects local input and sends it in packets.
+pub struct RemoteSender<T> {
+    /// The transport sending packets.
+    pub transport: T,
+    time: f64,
+    // Inputs since the last packet with their time.
+    pending: Vec<(f64, Input)>,
+}
+
+impl<T: Transport> RemoteSender<T> {
+    /// Creates a new sender starting at time 0.
+    pub fn new(transport: T) -> RemoteSender<T> {
+        RemoteSender {
+            transport: transport,
+            time: 0.0,
+            pending: vec![],
+        }
+    }
+
+    /// Advances time.
+    pub fn update(&mut self, dt: f64) {
+        self.time += dt;
+    }
+
+    /// Queues an input for the next packet.
+    pub fn handle_input(&mut self, input: &Input) {
+        self.pending.push((self.time, input.clone()));
+    }
+
+    /// Sends the queued inputs in a packet, if any.
+    pub fn flush(&mut self) -> io::Result<()> {
+        if self.pending.is_empty() { return Ok(()); }
+        let mut packet = vec![];
+        wire::write_header(&mut packet);
+        let mut encoder = Encoder::new();
+        for &(time, ref input) in &self.pending {
+            encoder.encode(time, input, &mut packet);
+        }
+        self.pending.clear();
+        self.transport.send(&packet)
+    }
+}
+
+/// Receives packets and reconstructs the remote input.
+pub struct RemoteReceiver<T> {
+    /// The transport receiving packets.
+    pub transport: T,
+    /// When set, gamepad ids are replaced by this id,
+    /// so the remote gamepad shows up as a local virtual gamepad.
+    pub gamepad_id: Option<u32>,
+}
+
+impl<T: Transport> RemoteReceiver<T> {
+    /// Creates a new receiver.
+    pub fn new(transport: T) -> RemoteReceiver<T> {
+        RemoteReceiver {
+            transport: transport,
+            gamepad_id: None,
+        }
+    }
+
+    /// Receives all available inputs with their remote time in seconds.
+    ///
+    /// Packets that can not be decoded are skipped, and their errors
+    /// returned with the inputs of the other packets.
+    /// Receiving stops at the first error of the transport.
+    pub fn receive(&mut self) -> (Vec<(f64, Input)>, Vec<RemoteError>) {
+        let mut inputs = vec![];
+        let mut errors = vec![];
+        loop {
+            match self.transport.receive() {
+                Ok(Some(packet)) => match self.decode(&packet) {
+                    Ok(decoded) => inputs.extend(decoded),
+                    Err(err) => errors.push(RemoteError::Wire(err)),
+                },
+                Ok(None) => break,
+                Err(err) => {
+                    errors.push(RemoteError::Io(err));
+                    break;
+                }
+            }
+        }
+        (inputs, errors)
+    }
+
+    fn decode(&self, packet: &[u8]) -> Result<Vec<(f64, Input)>, WireError> {
+        let mut inputs = vec![];
+        let (_, mut pos) = wire::read_header(packet)?;
+        let mut decoder = Decoder::new();
+        while pos < packet.len() {
+            let (time, input, n) = decoder.decode(&packet[pos..])?;
+            let input = match self.gamepad_id {
+                Some(id) => input.map_gamepad_id(|_| id),
+                None => input,
+            };
+            inputs.push((time, input));
+            pos += n;
+        }
+        Ok(inputs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::collections::VecDeque;
+    use std::io;
+    use std::rc::Rc;
+
+    use super::*;
+    use { Button, GamepadButton, Input };
+
+    struct Loopback(Rc<RefCell<VecDeque<Vec<u8>>>>);
+
+    impl Transport for Loopback {
+        fn send(&mut self, packet: &[u8]) -> io::Result<()> {
+            self.0.borrow_mut().push_back(packet.to_vec());
+            Ok(())
+        }
+
+        fn receive(&mut self) -> io::Result<Option<Vec<u8>>> {
+            Ok(self.0.borrow_mut().pop_front())
+        }
+    }
+
+    #[test]
+    fn test_loopback() {
+        let queue = Rc::new(RefCell::new(VecDeque::new()));
+        let mut sender = RemoteSender::new(Loopback(queue.clone()));
+        let mut receiver = RemoteReceiver::new(Loopback(queue));
+        receiver.gamepad_id = Some(9);
+
+        sender.update(0.5);
+        sender.handle_input(&Input::Press(Button::Gamepad(0, GamepadButton::South)));
+        sender.flush().unwrap();
+        let (inputs, errors) = receiver.receive();
+        assert_eq!(inputs, vec![(0.5, Input::Press(Button::Gamepad(9, GamepadButton::South)))]);
+        assert!(errors.is_empty());
+        assert!(receiver.receive().0.is_empty());
+    }
+
+    #[test]
+    fn test_bad_packet() {
+        let queue = Rc::new(RefCell::new(VecDeque::new()));
+        let mut sender = RemoteSender::new(Loopback(queue.clone()));
+        let mut receiver = RemoteReceiver::new(Loopback(queue.clone()));
+
+        sender.handle_input(&Input::Focus(true));
+        sender.flush().unwrap();
+        queue.borrow_mut().push_back(b"bad".to_vec());
+        sender.handle_input(&Input::Focus(false));
+        sender.flush().unwrap();
+        let (inputs, errors) = receiver.receive();
+        assert_eq!(inputs, vec![(0.0, Input::Focus(true)), (0.0, Input::Focus(false))]);
+        assert_eq!(errors.len(), 1);
+    }
+}
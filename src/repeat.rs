@@ -0,0 +1,130 @@
+//! Uniform key repeat.
+//!
+//! Back-ends report key repeat differently, or not at all.
+//! `KeyRepeat` drops the repeated presses from the back-end
+//! and synthesizes its own with the configured timing.
+
+use std::collections::HashSet;
+
+use { Button, Input, Key };
+
+/// Synthesizes repeated presses of the last pressed key.
+#[derive(Clone, Debug)]
+pub struct KeyRepeat {
+    // Delay before the first repeat and interval between repeats.
+    repeat: Option<(f64, f64)>,
+    held: HashSet<Key>,
+    // The repeating key and the time until its next repeat.
+    repeating: Option<(Key, f64)>,
+}
+
+impl KeyRepeat {
+    /// Creates a new key repeat with a delay and interval in seconds.
+    pub fn new(delay: f64, interval: f64) -> KeyRepeat {
+        KeyRepeat {
+            repeat: Some((delay, interval)),
+            held: HashSet::new(),
+            repeating: None,
+        }
+    }
+
+    /// Sets the delay before the first repeat and the interval
+    /// between repeats in seconds.
+    pub fn set_repeat(&mut self, delay: f64, interval: f64) {
+        self.repeat = Some((delay, interval));
+    }
+
+    /// Disables key repeat.
+    pub fn disable_repeat(&mut self) {
+        self.repeat = None;
+        self.repeating = None;
+    }
+
+    /// Returns the delay and interval in seconds, if repeat is enabled.
+    pub fn repeat(&self) -> Option<(f64, f64)> {
+        self.repeat
+    }
+
+    /// Processes an input, returning the inputs to pass on.
+    ///
+    /// Presses of keys that are already held are dropped.
+    pub fn handle_input(&mut self, input: &Input) -> Vec<Input> {
+        match *input {
+            Input::Press(Button::Keyboard(key)) => {
+                if !self.held.insert(key) { return vec![]; }
+                if let Some((delay, _)) = self.repeat {
+                    self.repeating = Some((key, delay));
+                }
+            }
+            Input::Release(Button::Keyboard(key)) => {
+                self.held.remove(&key);
+                if let Some((repeating, _)) = self.repeating {
+                    if repeating == key { self.repeating = None; }
+                }
+            }
+            Input::Focus(false) => {
+                // Releases are not reported while unfocused.
+                self.held.clear();
+                self.repeating = None;
+            }
+            _ => {}
+        }
+        vec![input.clone()]
+    }
+
+    /// Advances time, returning the repeated presses.
+    pub fn update(&mut self, dt: f64) -> Vec<Input> {
+        let mut out = vec![];
+        let interval = match self.repeat {
+            Some((_, interval)) if interval > 0.0 => interval,
+            _ => return out,
+        };
+        if let Some((key, ref mut time)) = self.repeating {
+            *time -= dt;
+            while *time <= 0.0 {
+                *time += interval;
+                out.push(Input::Press(Button::Keyboard(key)));
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use { Button, Input, Key };
+
+    fn press(key: Key) -> Input { Input::Press(Button::Keyboard(key)) }
+    fn release(key: Key) -> Input { Input::Release(Button::Keyboard(key)) }
+
+    #[test]
+    fn test_delay_and_interval() {
+        let mut repeat = KeyRepeat::new(0.5, 0.1);
+        assert_eq!(repeat.handle_input(&press(Key::A)), vec![press(Key::A)]);
+        assert!(repeat.update(0.4).is_empty());
+        assert_eq!(repeat.update(0.15), vec![press(Key::A)]);
+        assert_eq!(repeat.update(0.2), vec![press(Key::A), press(Key::A)]);
+        repeat.handle_input(&release(Key::A));
+        assert!(repeat.update(1.0).is_empty());
+    }
+
+    #[test]
+    fn test_duplicate_presses() {
+        let mut repeat = KeyRepeat::new(0.5, 0.1);
+        repeat.handle_input(&press(Key::A));
+        assert!(repeat.handle_input(&press(Key::A)).is_empty());
+        assert_eq!(repeat.handle_input(&release(Key::A)), vec![release(Key::A)]);
+        assert_eq!(repeat.handle_input(&press(Key::A)), vec![press(Key::A)]);
+    }
+
+    #[test]
+    fn test_focus_loss() {
+        let mut repeat = KeyRepeat::new(0.5, 0.1);
+        repeat.handle_input(&press(Key::A));
+        repeat.handle_input(&Input::Focus(false));
+        assert!(repeat.update(1.0).is_empty());
+        // The release was missed while unfocused.
+        assert_eq!(repeat.handle_input(&press(Key::A)), vec![press(Key::A)]);
+    }
+}
@@ -0,0 +1,192 @@
+//! Frame indexed input for rollback netcode.
+
+use std::collections::VecDeque;
+use std::hash::{ Hash, Hasher };
+
+use checksum::FnvHasher;
+
+/// The input of a player for a frame.
+#[derive(Copy, Clone, RustcDecodable, RustcEncodable, PartialEq, Eq, Hash, Debug)]
+pub enum FrameInput<T> {
+    /// The input was received from the player.
+    Confirmed(T),
+    /// The input was predicted from earlier input.
+    Predicted(T),
+}
+
+impl<T> FrameInput<T> {
+    /// Returns the input.
+    pub fn input(&self) -> &T {
+        match *self {
+            FrameInput::Confirmed(ref x) | FrameInput::Predicted(ref x) => x,
+        }
+    }
+
+    /// Returns `true` if the input was received from the player.
+    pub fn is_confirmed(&self) -> bool {
+        match *self {
+            FrameInput::Confirmed(_) => true,
+            FrameInput::Predicted(_) => false,
+        }
+    }
+}
+
+/// Stores the digested input of players per simulation frame,
+/// e.g. the pressed actions.
+///
+/// Missing input of remote players is predicted by repeating their
+/// last input. When the real input arrives and differs,
+/// the simulation rolls back to that frame.
+#[derive(Clone, Debug)]
+pub struct FrameInputBuffer<T> {
+    players: usize,
+    capacity: usize,
+    // The frame of the first entry.
+    start: u64,
+    frames: VecDeque<Vec<Option<FrameInput<T>>>>,
+}
+
+impl<T: Clone + PartialEq> FrameInputBuffer<T> {
+    /// Creates a new buffer keeping at most `capacity` frames of input.
+    pub fn new(players: usize, capacity: usize) -> FrameInputBuffer<T> {
+        FrameInputBuffer {
+            players: players,
+            capacity: capacity.max(1),
+            start: 0,
+            frames: VecDeque::new(),
+        }
+    }
+
+    /// Returns the oldest frame kept.
+    pub fn oldest_frame(&self) -> u64 {
+        self.start
+    }
+
+    /// Returns the input of a player in a frame.
+    pub fn get(&self, frame: u64, player: usize) -> Option<&FrameInput<T>> {
+        if frame < self.start { return None; }
+        self.frames.get((frame - self.start) as usize)
+            .and_then(|inputs| inputs.get(player))
+            .and_then(|input| input.as_ref())
+    }
+
+    /// Stores the received input of a player in a frame.
+    ///
+    /// Returns the frame to roll back to if the input
+    /// differs from the predicted input.
+    pub fn confirm(&mut self, frame: u64, player: usize, input: T) -> Option<u64> {
+        let rollback = match self.get(frame, player) {
+            Some(&FrameInput::Predicted(ref predicted)) if *predicted != input => Some(frame),
+            _ => None,
+        };
+        if let Some(slot) = self.slot(frame, player) {
+            *slot = Some(FrameInput::Confirmed(input));
+        }
+        // Later predictions were based on the old input.
+        if rollback.is_some() {
+            let mut f = frame + 1;
+            while let Some(&FrameInput::Predicted(_)) = self.get(f, player) {
+                let predicted = self.predict(f, player);
+                if let Some(slot) = self.slot(f, player) {
+                    *slot = predicted.map(FrameInput::Predicted);
+                }
+                f += 1;
+            }
+        }
+        rollback
+    }
+
+    /// Returns the input of a player in a frame,
+    /// predicting and storing it if it was not received.
+    pub fn input(&mut self, frame: u64, player: usize) -> Option<T> {
+        if let Some(input) = self.get(frame, player) {
+            return Some(input.input().clone());
+        }
+        let predicted = self.predict(frame, player);
+        if let Some(ref predicted) = predicted {
+            if let Some(slot) = self.slot(frame, player) {
+                *slot = Some(FrameInput::Predicted(predicted.clone()));
+            }
+        }
+        predicted
+    }
+
+    /// Returns the last frame up to which the input of all players
+    /// is confirmed.
+    pub fn confirmed_frame(&self) -> Option<u64> {
+        let n = self.frames.iter()
+            .take_while(|inputs| inputs.iter().all(|input| match *input {
+                Some(ref input) => input.is_confirmed(),
+                None => false,
+            }))
+            .count() as u64;
+        if n == 0 { None } else { Some(self.start + n - 1) }
+    }
+
+    /// Returns a checksum of the input of all players in a frame,
+    /// if the input of all players is confirmed.
+    ///
+    /// Peers compare checksums of confirmed frames to detect desyncs.
+    /// Like `checksum::checksum`, the result depends on the version
+    /// of Rust, so peers must be built with the same compiler.
+    pub fn checksum(&self, frame: u64) -> Option<u64>
+        where T: Hash
+    {
+        let mut hasher = FnvHasher::new();
+        for player in 0..self.players {
+            match self.get(frame, player) {
+                Some(&FrameInput::Confirmed(ref input)) => input.hash(&mut hasher),
+                _ => return None,
+            }
+        }
+        Some(hasher.finish())
+    }
+
+    // The latest input of a player before a frame.
+    fn predict(&self, frame: u64, player: usize) -> Option<T> {
+        let mut f = frame;
+        while f > self.start {
+            f -= 1;
+            if let Some(input) = self.get(f, player) {
+                return Some(input.input().clone());
+            }
+        }
+        None
+    }
+
+    // Returns the slot of a player in a frame, adding frames as needed
+    // and forgetting frames beyond the capacity.
+    fn slot(&mut self, frame: u64, player: usize) -> Option<&mut Option<FrameInput<T>>> {
+        if frame < self.start || player >= self.players { return None; }
+        while frame >= self.start + self.frames.len() as u64 {
+            self.frames.push_back(vec![None; self.players]);
+        }
+        while self.frames.len() > self.capacity {
+            self.frames.pop_front();
+            self.start += 1;
+        }
+        if frame < self.start { return None; }
+        let i = (frame - self.start) as usize;
+        Some(&mut self.frames[i][player])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rollback() {
+        let mut buffer = FrameInputBuffer::new(2, 8);
+        buffer.confirm(0, 0, 1);
+        buffer.confirm(0, 1, 1);
+        assert_eq!(buffer.confirmed_frame(), Some(0));
+        buffer.confirm(1, 0, 2);
+        assert_eq!(buffer.input(1, 1), Some(1));
+        assert_eq!(buffer.input(2, 1), Some(1));
+        assert_eq!(buffer.confirm(1, 1, 3), Some(1));
+        assert_eq!(buffer.get(2, 1), Some(&FrameInput::Predicted(3)));
+        assert_eq!(buffer.confirm(2, 1, 3), None);
+        assert_eq!(buffer.confirmed_frame(), Some(1));
+    }
+}
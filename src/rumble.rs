@@ -0,0 +1,133 @@
+//! Scheduled rumble of gamepads.
+//!
+//! Games queue rumble envelopes once, and back-ends apply the motor
+//! strengths computed each frame.
+
+/// Strength of a rumble over time.
+#[derive(Copy, Clone, RustcDecodable, RustcEncodable, PartialEq, Debug)]
+pub struct Envelope {
+    /// Strength of the low and high frequency motors from 0 to 1.
+    pub strength: [f64; 2],
+    /// Seconds to ramp up to full strength.
+    pub attack: f64,
+    /// Seconds at full strength.
+    pub sustain: f64,
+    /// Seconds to ramp down from full strength.
+    pub decay: f64,
+}
+
+impl Envelope {
+    /// Creates a new envelope at constant strength for `duration` seconds.
+    pub fn new(strength: [f64; 2], duration: f64) -> Envelope {
+        Envelope {
+            strength: strength,
+            attack: 0.0,
+            sustain: duration,
+            decay: 0.0,
+        }
+    }
+
+    /// Returns the duration of the envelope in seconds.
+    pub fn duration(&self) -> f64 {
+        self.attack + self.sustain + self.decay
+    }
+
+    /// Returns the motor strengths at a time in seconds from the start.
+    pub fn strength_at(&self, time: f64) -> [f64; 2] {
+        let scale = if time < 0.0 || time >= self.duration() {
+            0.0
+        } else if time < self.attack {
+            time / self.attack
+        } else if time < self.attack + self.sustain {
+            1.0
+        } else {
+            (self.duration() - time) / self.decay
+        };
+        [self.strength[0] * scale, self.strength[1] * scale]
+    }
+}
+
+/// Plays queued rumble envelopes on gamepads.
+///
+/// Overlapping envelopes on the same gamepad use the strongest motor values.
+#[derive(Clone, Debug)]
+pub struct RumbleScheduler {
+    time: f64,
+    // Gamepad id, start time and envelope.
+    pulses: Vec<(u32, f64, Envelope)>,
+}
+
+impl RumbleScheduler {
+    /// Creates a new scheduler without rumble.
+    pub fn new() -> RumbleScheduler {
+        RumbleScheduler {
+            time: 0.0,
+            pulses: vec![],
+        }
+    }
+
+    /// Plays an envelope on a gamepad after `delay` seconds.
+    pub fn schedule(&mut self, id: u32, delay: f64, envelope: Envelope) {
+        self.pulses.push((id, self.time + delay.max(0.0), envelope));
+    }
+
+    /// Stops all rumble on a gamepad.
+    pub fn stop(&mut self, id: u32) {
+        self.pulses.retain(|&(pulse_id, _, _)| pulse_id != id);
+    }
+
+    /// Advances time and forgets finished envelopes.
+    pub fn update(&mut self, dt: f64) {
+        self.time += dt;
+        let time = self.time;
+        self.pulses.retain(|&(_, start, ref envelope)| time - start < envelope.duration());
+    }
+
+    /// Returns the current strength of the low and high frequency motors.
+    pub fn strength(&self, id: u32) -> [f64; 2] {
+        let mut strength = [0.0; 2];
+        for &(pulse_id, start, ref envelope) in &self.pulses {
+            if pulse_id != id { continue; }
+            let s = envelope.strength_at(self.time - start);
+            strength[0] = s[0].max(strength[0]);
+            strength[1] = s[1].max(strength[1]);
+        }
+        strength
+    }
+
+    /// Returns `true` if a gamepad has queued or playing rumble.
+    pub fn is_active(&self, id: u32) -> bool {
+        self.pulses.iter().any(|&(pulse_id, _, _)| pulse_id == id)
+    }
+}
+
+impl Default for RumbleScheduler {
+    fn default() -> RumbleScheduler { RumbleScheduler::new() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_envelope() {
+        let envelope = Envelope {
+            strength: [1.0, 0.5],
+            attack: 1.0,
+            sustain: 1.0,
+            decay: 2.0,
+        };
+        assert_eq!(envelope.strength_at(0.5), [0.5, 0.25]);
+        assert_eq!(envelope.strength_at(1.5), [1.0, 0.5]);
+        assert_eq!(envelope.strength_at(3.0), [0.5, 0.25]);
+        assert_eq!(envelope.strength_at(4.0), [0.0, 0.0]);
+
+        let mut rumble = RumbleScheduler::new();
+        rumble.schedule(0, 1.0, envelope);
+        assert_eq!(rumble.strength(0), [0.0, 0.0]);
+        rumble.update(2.5);
+        assert_eq!(rumble.strength(0), [1.0, 0.5]);
+        rumble.update(3.0);
+        assert!(!rumble.is_active(0));
+    }
+}
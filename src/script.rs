@@ -0,0 +1,125 @@
+//! Scripted input for testing.
+//!
+//! Describes input declaratively and produces a timed stream of inputs:
+//!
+//! ```
+//! use input::{ Button, Key };
+//! use input::script::Script;
+//!
+//! let a = Button::Keyboard(Key::A);
+//! let events = Script::new()
+//!     .press(0.0, a)
+//!     .release(0.12, a)
+//!     .move_cursor(0.0, [10.0, 20.0], 0.05)
+//!     .events();
+//! ```
+
+use recording::Macro;
+use { Button, Input, Motion };
+
+/// A script of timed inputs.
+///
+/// Times are in seconds since the start of the script.
+#[derive(Clone, Debug)]
+pub struct Script {
+    /// Cursor positions sampled per second during cursor movement.
+    pub cursor_rate: f64,
+    events: Vec<(f64, Input)>,
+    cursor: [f64; 2],
+}
+
+impl Script {
+    /// Creates a new empty script.
+    pub fn new() -> Script {
+        Script {
+            cursor_rate: 120.0,
+            events: vec![],
+            cursor: [0.0, 0.0],
+        }
+    }
+
+    /// Adds an input at a time.
+    pub fn input(mut self, time: f64, input: Input) -> Script {
+        self.events.push((time, input));
+        self
+    }
+
+    /// Presses a button at a time.
+    pub fn press(self, time: f64, button: Button) -> Script {
+        self.input(time, Input::Press(button))
+    }
+
+    /// Releases a button at a time.
+    pub fn release(self, time: f64, button: Button) -> Script {
+        self.input(time, Input::Release(button))
+    }
+
+    /// Enters text at a time.
+    pub fn text(self, time: f64, text: &str) -> Script {
+        self.input(time, Input::Text(text.to_string()))
+    }
+
+    /// Moves the cursor in a straight line to a position over a duration.
+    ///
+    /// Starts from the end position of the previous cursor movement,
+    /// or the origin.
+    pub fn move_cursor(mut self, time: f64, to: [f64; 2], duration: f64) -> Script {
+        let steps = (duration * self.cursor_rate).ceil().max(1.0) as u32;
+        let from = self.cursor;
+        for i in 1..steps + 1 {
+            let t = i as f64 / steps as f64;
+            let x = from[0] + (to[0] - from[0]) * t;
+            let y = from[1] + (to[1] - from[1]) * t;
+            self.events.push((time + duration * t,
+                              Input::Move(Motion::MouseCursor(x, y))));
+        }
+        self.cursor = to;
+        self
+    }
+
+    /// Returns the inputs with their times in chronological order.
+    ///
+    /// Inputs at the same time keep the order they were added in.
+    /// Inputs at a NaN time are sorted last.
+    pub fn events(&self) -> Vec<(f64, Input)> {
+        let mut events = self.events.clone();
+        events.sort_by(|a, b| a.0.is_nan().cmp(&b.0.is_nan()).then(a.0.total_cmp(&b.0)));
+        events
+    }
+
+    /// Converts the script to a macro for playback.
+    pub fn to_macro(&self, name: &str) -> Macro {
+        let mut recorded = Macro::new(name);
+        let mut last = 0.0;
+        for (time, input) in self.events() {
+            recorded.events.push((time - last, input));
+            last = time;
+        }
+        recorded
+    }
+}
+
+impl Default for Script {
+    fn default() -> Script { Script::new() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Key;
+
+    #[test]
+    fn test_events() {
+        let a = Button::Keyboard(Key::A);
+        let events = Script::new()
+            .release(0.5, a)
+            .input(::std::f64::NAN, Input::Focus(false))
+            .press(0.0, a)
+            .text(0.5, "a")
+            .events();
+        assert_eq!(events[0], (0.0, Input::Press(a)));
+        assert_eq!(events[1], (0.5, Input::Release(a)));
+        assert_eq!(events[2], (0.5, Input::Text("a".to_string())));
+        assert!(events[3].0.is_nan());
+    }
+}
@@ -0,0 +1,61 @@
+//! Secure input mode.
+//!
+//! While secure mode is enabled, e.g. while a password field has focus,
+//! keyboard and text input is sensitive. Recording, macros, statistics
+//! and logging ignore sensitive input, so keystrokes do not leak into
+//! replays or logs. Other input, e.g. of the mouse, is still handled.
+
+use std::sync::atomic::{ AtomicBool, Ordering };
+
+use { Button, Input };
+
+static SECURE_MODE: AtomicBool = AtomicBool::new(false);
+
+/// Enables or disables secure mode.
+pub fn secure_mode(enabled: bool) {
+    SECURE_MODE.store(enabled, Ordering::SeqCst);
+}
+
+/// Returns `true` if secure mode is enabled.
+pub fn is_secure_mode() -> bool {
+    SECURE_MODE.load(Ordering::SeqCst)
+}
+
+/// Returns `true` if the input must not be stored or logged.
+///
+/// In secure mode, keyboard and text input is sensitive.
+pub fn is_sensitive(input: &Input) -> bool {
+    sensitive(input, is_secure_mode())
+}
+
+// Takes the mode explicitly, so stages can be tested
+// without racing on the process-global flag.
+pub(crate) fn sensitive(input: &Input, secure_mode: bool) -> bool {
+    if !secure_mode { return false; }
+    match *input {
+        Input::Press(Button::Keyboard(_))
+      | Input::Release(Button::Keyboard(_))
+      | Input::Text(_) => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use { Key, MouseButton };
+
+    #[test]
+    fn test_sensitive() {
+        let key = Input::Press(Button::Keyboard(Key::A));
+        let mouse = Input::Press(Button::Mouse(MouseButton::Left));
+        let text = Input::Text("a".to_string());
+        assert!(!sensitive(&key, false));
+        assert!(!sensitive(&text, false));
+        assert!(sensitive(&key, true));
+        assert!(sensitive(&Input::Release(Button::Keyboard(Key::A)), true));
+        assert!(sensitive(&text, true));
+        assert!(!sensitive(&mouse, true));
+        assert!(!sensitive(&Input::Focus(false), true));
+    }
+}
@@ -0,0 +1,141 @@
+//! Sensitivity, response curves and dead zones of mice and gamepads.
+//!
+//! Profiles are serializable, so they can be stored with the bindings
+//! of a user.
+
+use std::collections::HashMap;
+
+use { Input, Motion };
+
+/// Sensitivity settings of a device.
+#[derive(Copy, Clone, RustcDecodable, RustcEncodable, PartialEq, Debug)]
+pub struct SensitivityProfile {
+    /// Multiplier of relative mouse motion and stick axes.
+    pub sensitivity: f64,
+    /// Exponent of the stick response curve, 1 for linear.
+    pub curve: f64,
+    /// Stick values below the dead zone are reported as 0.
+    pub dead_zone: f64,
+}
+
+impl SensitivityProfile {
+    /// Creates a new linear profile without dead zone.
+    pub fn new() -> SensitivityProfile {
+        SensitivityProfile {
+            sensitivity: 1.0,
+            curve: 1.0,
+            dead_zone: 0.0,
+        }
+    }
+
+    /// Applies the dead zone, curve and sensitivity to an axis value
+    /// from -1 to 1.
+    pub fn axis(&self, value: f64) -> f64 {
+        let magnitude = value.abs();
+        if magnitude <= self.dead_zone { return 0.0; }
+        // Rescale so the output starts at 0 at the edge of the dead zone.
+        let x = ((magnitude - self.dead_zone) / (1.0 - self.dead_zone)).min(1.0);
+        let y = x.powf(self.curve) * self.sensitivity;
+        if value < 0.0 { -y } else { y }
+    }
+}
+
+impl Default for SensitivityProfile {
+    fn default() -> SensitivityProfile { SensitivityProfile::new() }
+}
+
+/// Applies sensitivity profiles to the mouse and to gamepads.
+#[derive(Clone, Debug)]
+pub struct Sensitivity {
+    /// The profile of the mouse, only the sensitivity is used.
+    pub mouse: SensitivityProfile,
+    gamepads: HashMap<u32, SensitivityProfile>,
+}
+
+impl Sensitivity {
+    /// Creates new sensitivity settings with default profiles.
+    pub fn new() -> Sensitivity {
+        Sensitivity {
+            mouse: SensitivityProfile::new(),
+            gamepads: HashMap::new(),
+        }
+    }
+
+    /// Sets the profile of a gamepad, or the default profile with `None`.
+    pub fn set_gamepad(&mut self, id: u32, profile: Option<SensitivityProfile>) {
+        match profile {
+            Some(profile) => { self.gamepads.insert(id, profile); }
+            None => { self.gamepads.remove(&id); }
+        }
+    }
+
+    /// Returns the profile of a gamepad.
+    pub fn gamepad(&self, id: u32) -> SensitivityProfile {
+        self.gamepads.get(&id).cloned().unwrap_or_default()
+    }
+
+    /// Applies the profiles to an input.
+    ///
+    /// Axis values are clamped to the range of the axis.
+    pub fn handle_input(&self, input: &Input) -> Input {
+        match *input {
+            Input::Move(Motion::MouseRelative(x, y)) => {
+                let s = self.mouse.sensitivity;
+                Input::Move(Motion::MouseRelative(x * s, y * s))
+            }
+            Input::Move(Motion::GamepadAxis(id, axis, value)) => {
+                let value = self.gamepad(id).axis(value);
+                let range = axis.range();
+                Input::Move(Motion::GamepadAxis(id, axis, value.max(range[0]).min(range[1])))
+            }
+            ref x => x.clone(),
+        }
+    }
+}
+
+impl Default for Sensitivity {
+    fn default() -> Sensitivity { Sensitivity::new() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use GamepadAxis;
+
+    #[test]
+    fn test_profile() {
+        let mut profile = SensitivityProfile::new();
+        assert_eq!(profile.axis(-0.5), -0.5);
+        profile.dead_zone = 0.5;
+        assert_eq!(profile.axis(0.25), 0.0);
+        assert_eq!(profile.axis(-0.5), 0.0);
+        assert_eq!(profile.axis(0.75), 0.5);
+        assert_eq!(profile.axis(1.0), 1.0);
+        profile.curve = 2.0;
+        profile.sensitivity = 2.0;
+        assert_eq!(profile.axis(-0.75), -0.5);
+    }
+
+    #[test]
+    fn test_sensitivity() {
+        let axis = |id, axis, value| Input::Move(Motion::GamepadAxis(id, axis, value));
+        let mut sensitivity = Sensitivity::new();
+        sensitivity.mouse.sensitivity = 2.0;
+        sensitivity.set_gamepad(1, Some(SensitivityProfile {
+            sensitivity: 3.0,
+            curve: 1.0,
+            dead_zone: 0.0,
+        }));
+        assert_eq!(sensitivity.handle_input(&Input::Move(Motion::MouseRelative(1.0, -2.0))),
+                   Input::Move(Motion::MouseRelative(2.0, -4.0)));
+        assert_eq!(sensitivity.handle_input(&axis(0, GamepadAxis::LeftStickX, 0.5)),
+                   axis(0, GamepadAxis::LeftStickX, 0.5));
+        assert_eq!(sensitivity.handle_input(&axis(1, GamepadAxis::LeftStickX, -0.5)),
+                   axis(1, GamepadAxis::LeftStickX, -1.0));
+        assert_eq!(sensitivity.handle_input(&axis(1, GamepadAxis::LeftTrigger, 0.25)),
+                   axis(1, GamepadAxis::LeftTrigger, 0.75));
+
+        sensitivity.set_gamepad(1, None);
+        assert_eq!(sensitivity.gamepad(1), SensitivityProfile::new());
+    }
+}
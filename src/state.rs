@@ -0,0 +1,91 @@
+//! Tracking of held buttons and window focus.
+
+use std::collections::HashSet;
+use std::collections::hash_set::Iter;
+
+use { Button, Input };
+
+/// Keeps track of which buttons are held down.
+#[derive(Clone, Debug)]
+pub struct InputState {
+    /// Release all held buttons when the window loses focus.
+    ///
+    /// Back-ends usually do not send release events for buttons
+    /// that were let go while the window was unfocused.
+    pub release_on_focus_loss: bool,
+    pressed: HashSet<Button>,
+    focus: bool,
+}
+
+impl InputState {
+    /// Creates a new input state with no buttons held.
+    pub fn new() -> InputState {
+        InputState {
+            release_on_focus_loss: false,
+            pressed: HashSet::new(),
+            focus: true,
+        }
+    }
+
+    /// Change state depending on input.
+    ///
+    /// Returns the release events synthesized for held buttons
+    /// when focus is lost and `release_on_focus_loss` is set.
+    pub fn handle_input(&mut self, input: &Input) -> Vec<Input> {
+        match *input {
+            Input::Press(button) => { self.pressed.insert(button); }
+            Input::Release(button) => { self.pressed.remove(&button); }
+            Input::Focus(focus) => {
+                self.focus = focus;
+                if !focus && self.release_on_focus_loss {
+                    return self.pressed.drain()
+                        .map(|button| Input::Release(button))
+                        .collect();
+                }
+            }
+            _ => {}
+        }
+        vec![]
+    }
+
+    /// Returns `true` if the button is held down.
+    pub fn is_pressed(&self, button: &Button) -> bool {
+        self.pressed.contains(button)
+    }
+
+    /// Returns an iterator over the held buttons.
+    pub fn pressed<'a>(&'a self) -> Iter<'a, Button> {
+        self.pressed.iter()
+    }
+
+    /// Returns `true` if the window has focus.
+    pub fn has_focus(&self) -> bool {
+        self.focus
+    }
+}
+
+impl Default for InputState {
+    fn default() -> InputState { InputState::new() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use { Button, Input, Key };
+
+    #[test]
+    fn test_release_on_focus_loss() {
+        let w = Button::Keyboard(Key::W);
+        let mut state = InputState::new();
+        state.handle_input(&Input::Press(w));
+        assert!(state.handle_input(&Input::Focus(false)).is_empty());
+        assert!(state.is_pressed(&w));
+
+        state.release_on_focus_loss = true;
+        state.handle_input(&Input::Focus(true));
+        assert_eq!(state.handle_input(&Input::Focus(false)),
+                   vec![Input::Release(w)]);
+        assert!(!state.is_pressed(&w));
+        assert!(!state.has_focus());
+    }
+}
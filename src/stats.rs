@@ -0,0 +1,161 @@
+//! Statistics collected from user input.
+
+use std::collections::{ HashMap, VecDeque };
+
+use { Button, Input };
+use secure;
+
+/// A snapshot of input statistics.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Snapshot {
+    /// Seconds since collection started.
+    pub time: f64,
+    /// Total number of inputs.
+    pub events: u64,
+    /// Number of presses per button.
+    pub presses: HashMap<Button, u64>,
+    /// Button presses per minute within the time window.
+    pub actions_per_minute: f64,
+    /// Inputs per second within the time window.
+    pub events_per_second: f64,
+}
+
+/// Aggregates counts and rates of user input.
+#[derive(Clone, Debug)]
+pub struct Stats {
+    /// Seconds of recent input used to compute rates.
+    pub window: f64,
+    time: f64,
+    events: u64,
+    presses: HashMap<Button, u64>,
+    // Time of recent inputs and whether they were presses.
+    recent: VecDeque<(f64, bool)>,
+}
+
+impl Stats {
+    /// Creates new statistics computing rates over `window` seconds.
+    pub fn new(window: f64) -> Stats {
+        Stats {
+            window: window,
+            time: 0.0,
+            events: 0,
+            presses: HashMap::new(),
+            recent: VecDeque::new(),
+        }
+    }
+
+    /// Counts an input.
+    ///
+    /// Sensitive inputs in secure mode are ignored.
+    pub fn handle_input(&mut self, input: &Input) {
+        self.handle(input, secure::is_secure_mode());
+    }
+
+    fn handle(&mut self, input: &Input, secure_mode: bool) {
+        if secure::sensitive(input, secure_mode) { return; }
+        self.events += 1;
+        let press = match *input {
+            Input::Press(button) => {
+                *self.presses.entry(button).or_insert(0) += 1;
+                true
+            }
+            _ => false,
+        };
+        self.recent.push_back((self.time, press));
+    }
+
+    /// Advances time and forgets inputs that left the time window.
+    pub fn update(&mut self, dt: f64) {
+        self.time += dt;
+        while let Some(&(t, _)) = self.recent.front() {
+            if self.time - t <= self.window { break; }
+            self.recent.pop_front();
+        }
+    }
+
+    /// Returns the number of times a button was pressed.
+    pub fn presses(&self, button: &Button) -> u64 {
+        self.presses.get(button).cloned().unwrap_or(0)
+    }
+
+    /// Returns button presses per minute within the time window.
+    pub fn actions_per_minute(&self) -> f64 {
+        let presses = self.recent.iter().filter(|&&(_, press)| press).count();
+        self.rate(presses) * 60.0
+    }
+
+    /// Returns inputs per second within the time window.
+    pub fn events_per_second(&self) -> f64 {
+        self.rate(self.recent.len())
+    }
+
+    /// Returns a snapshot of the current statistics.
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            time: self.time,
+            events: self.events,
+            presses: self.presses.clone(),
+            actions_per_minute: self.actions_per_minute(),
+            events_per_second: self.events_per_second(),
+        }
+    }
+
+    /// Clears all statistics.
+    pub fn reset(&mut self) {
+        self.time = 0.0;
+        self.events = 0;
+        self.presses.clear();
+        self.recent.clear();
+    }
+
+    fn rate(&self, count: usize) -> f64 {
+        // Do not inflate rates before a full window has passed.
+        let window = self.window.min(self.time);
+        if window <= 0.0 { 0.0 } else { count as f64 / window }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use { Key, MouseButton };
+
+    #[test]
+    fn test_counts() {
+        let a = Button::Keyboard(Key::A);
+        let mut stats = Stats::new(2.0);
+        stats.handle(&Input::Press(a), false);
+        stats.handle(&Input::Release(a), false);
+        stats.update(1.0);
+        stats.handle(&Input::Press(a), false);
+        stats.handle(&Input::Focus(true), false);
+        stats.update(1.0);
+        assert_eq!(stats.presses(&a), 2);
+        assert_eq!(stats.presses(&Button::Keyboard(Key::B)), 0);
+        assert_eq!(stats.events_per_second(), 2.0);
+        assert_eq!(stats.actions_per_minute(), 60.0);
+
+        stats.update(1.5);
+        assert_eq!(stats.events_per_second(), 0.0);
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot.events, 4);
+        assert_eq!(snapshot.time, 3.5);
+
+        stats.reset();
+        assert_eq!(stats.snapshot().events, 0);
+        assert_eq!(stats.presses(&a), 0);
+    }
+
+    #[test]
+    fn test_secure_mode() {
+        let a = Button::Keyboard(Key::A);
+        let left = Button::Mouse(MouseButton::Left);
+        let mut stats = Stats::new(1.0);
+        stats.handle(&Input::Press(a), true);
+        stats.handle(&Input::Text("a".to_string()), true);
+        stats.handle(&Input::Press(left), true);
+        assert_eq!(stats.presses(&a), 0);
+        assert_eq!(stats.presses(&left), 1);
+        assert_eq!(stats.snapshot().events, 1);
+    }
+}
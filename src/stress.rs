@@ -0,0 +1,161 @@
+//! Generation of input floods for benchmarks.
+
+use { Button, Input, Key, Motion };
+use touch::{ Touch, TouchPhase };
+
+// Keys mashed by the generator.
+static MASHED_KEYS: &'static [Key] = &[
+    Key::W, Key::A, Key::S, Key::D, Key::Space, Key::LShift,
+    Key::Up, Key::Down, Key::Left, Key::Right,
+];
+
+/// Generates floods of realistic inputs, e.g. for benchmarking.
+///
+/// The generated inputs are deterministic for a given seed.
+#[derive(Clone, Debug)]
+pub struct StressGenerator {
+    /// Mouse samples per second, e.g. 8000 for a 8 kHz mouse.
+    ///
+    /// Each sample generates a relative and a cursor motion input.
+    pub mouse_rate: f64,
+    /// Key presses and releases per second.
+    pub key_rate: f64,
+    /// Touch inputs per second, e.g. 120 for a 120 Hz touchscreen.
+    ///
+    /// A single contact at a time touches, swipes and lifts.
+    pub touch_rate: f64,
+    /// The size of the window bounding the cursor and touches.
+    pub size: [f64; 2],
+    rng: u64,
+    mouse_time: f64,
+    key_time: f64,
+    touch_time: f64,
+    cursor: [f64; 2],
+    held: Vec<Key>,
+    // The id of the last contact and the active contact.
+    touch_id: i64,
+    touch: Option<Touch>,
+}
+
+impl StressGenerator {
+    /// Creates a new generator from a seed.
+    pub fn new(seed: u64) -> StressGenerator {
+        StressGenerator {
+            mouse_rate: 8000.0,
+            key_rate: 20.0,
+            touch_rate: 120.0,
+            size: [1920.0, 1080.0],
+            // The random generator state must not be zero.
+            rng: seed | 1,
+            mouse_time: 0.0,
+            key_time: 0.0,
+            touch_time: 0.0,
+            cursor: [0.0, 0.0],
+            held: vec![],
+            touch_id: 0,
+            touch: None,
+        }
+    }
+
+    /// Advances time, pushing the generated inputs to `out`.
+    pub fn update(&mut self, dt: f64, out: &mut Vec<Input>) {
+        self.mouse_time += dt * self.mouse_rate;
+        while self.mouse_time >= 1.0 {
+            self.mouse_time -= 1.0;
+            let dx = self.random() * 8.0 - 4.0;
+            let dy = self.random() * 8.0 - 4.0;
+            self.cursor[0] = (self.cursor[0] + dx).max(0.0).min(self.size[0]);
+            self.cursor[1] = (self.cursor[1] + dy).max(0.0).min(self.size[1]);
+            out.push(Input::Move(Motion::MouseRelative(dx, dy)));
+            out.push(Input::Move(Motion::MouseCursor(self.cursor[0], self.cursor[1])));
+        }
+        self.key_time += dt * self.key_rate;
+        while self.key_time >= 1.0 {
+            self.key_time -= 1.0;
+            if !self.held.is_empty() && self.random() < 0.5 {
+                let i = self.index(self.held.len());
+                let key = self.held.swap_remove(i);
+                out.push(Input::Release(Button::Keyboard(key)));
+            } else {
+                let key = MASHED_KEYS[self.index(MASHED_KEYS.len())];
+                if !self.held.contains(&key) { self.held.push(key); }
+                out.push(Input::Press(Button::Keyboard(key)));
+            }
+        }
+        self.touch_time += dt * self.touch_rate;
+        while self.touch_time >= 1.0 {
+            self.touch_time -= 1.0;
+            let active = self.touch;
+            let touch = match active {
+                None => {
+                    self.touch_id += 1;
+                    let x = self.random() * self.size[0];
+                    let y = self.random() * self.size[1];
+                    Touch::new(self.touch_id, TouchPhase::Began, [x, y])
+                }
+                Some(touch) if self.random() < 0.05 =>
+                    Touch::new(touch.id, TouchPhase::Ended, touch.position),
+                Some(touch) => {
+                    let x = touch.position[0] + self.random() * 16.0 - 8.0;
+                    let y = touch.position[1] + self.random() * 16.0 - 8.0;
+                    Touch::new(touch.id, TouchPhase::Moved,
+                               [x.max(0.0).min(self.size[0]), y.max(0.0).min(self.size[1])])
+                }
+            };
+            self.touch = if touch.phase.is_end() { None } else { Some(touch) };
+            out.push(Input::Touch(touch));
+        }
+    }
+
+    fn random(&mut self) -> f64 {
+        random(&mut self.rng)
+    }
+
+    fn index(&mut self, len: usize) -> usize {
+        (self.random() * len as f64) as usize
+    }
+}
+
+// Returns a random number in the range `[0, 1)` using xorshift64*.
+//
+// The state must not be zero.
+pub(crate) fn random(rng: &mut u64) -> f64 {
+    *rng ^= *rng >> 12;
+    *rng ^= *rng << 25;
+    *rng ^= *rng >> 27;
+    let x = rng.wrapping_mul(2685821657736338717);
+    (x >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deterministic() {
+        let mut a = StressGenerator::new(7);
+        let mut b = StressGenerator::new(7);
+        let (mut out_a, mut out_b) = (vec![], vec![]);
+        for _ in 0..100 {
+            a.update(0.01, &mut out_a);
+            b.update(0.01, &mut out_b);
+        }
+        assert_eq!(out_a, out_b);
+
+        let mut c = StressGenerator::new(8);
+        let mut out_c = vec![];
+        for _ in 0..100 { c.update(0.01, &mut out_c); }
+        assert!(out_a != out_c);
+    }
+
+    #[test]
+    fn test_rates() {
+        let mut generator = StressGenerator::new(1);
+        let mut out = vec![];
+        generator.update(1.0, &mut out);
+        let count = |f: &dyn Fn(&Input) -> bool| out.iter().filter(|x| f(x)).count();
+        assert_eq!(count(&|x| match *x { Input::Move(_) => true, _ => false }), 16000);
+        assert_eq!(count(&|x| x.as_press().is_some() || x.as_release().is_some()), 20);
+        assert_eq!(count(&|x| match *x { Input::Touch(_) => true, _ => false }), 120);
+    }
+}
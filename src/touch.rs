@@ -0,0 +1,447 @@
+//! Back-end agnostic touch input.
+
+use std::collections::{ HashMap, HashSet };
+
+use Input;
+
+#[cfg(feature = "quickcheck")]
+use quickcheck::{ Arbitrary, Gen };
+
+/// The phase of a touch contact.
+#[derive(Copy, Clone, RustcDecodable, RustcEncodable, PartialEq, Eq, Hash, Debug)]
+pub enum TouchPhase {
+    /// The contact touched the surface.
+    Began,
+    /// The contact moved.
+    Moved,
+    /// The contact touches the surface without moving.
+    Stationary,
+    /// The contact was lifted from the surface.
+    Ended,
+    /// The contact was cancelled, e.g. by the system or a rejected palm.
+    Cancelled,
+}
+
+impl TouchPhase {
+    /// Returns `true` if the phase is the last of a contact.
+    pub fn is_end(&self) -> bool {
+        match *self {
+            TouchPhase::Ended | TouchPhase::Cancelled => true,
+            _ => false,
+        }
+    }
+}
+
+/// A touch contact on a surface.
+#[derive(Copy, Clone, RustcDecodable, RustcEncodable, PartialEq, Debug)]
+pub struct Touch {
+    /// The id of the contact, unique while it touches the surface.
+    pub id: i64,
+    /// The phase of the contact.
+    pub phase: TouchPhase,
+    /// x and y in window coordinates.
+    pub position: [f64; 2],
+    /// Pressure from 0 to 1, if reported by the back-end.
+    pub pressure: Option<f64>,
+    /// The major and minor radii of the contact ellipse
+    /// in window coordinates, if reported by the back-end.
+    pub radius: Option<[f64; 2]>,
+    /// The angle of the major axis in radians clockwise from the y axis,
+    /// if reported by the back-end.
+    pub orientation: Option<f64>,
+    /// Confidence from 0 to 1 that the contact is intentional,
+    /// e.g. not a resting palm, if reported by the back-end.
+    pub confidence: Option<f64>,
+}
+
+impl Touch {
+    /// Creates a new touch contact without contact geometry.
+    pub fn new(id: i64, phase: TouchPhase, position: [f64; 2]) -> Touch {
+        Touch {
+            id: id,
+            phase: phase,
+            position: position,
+            pressure: None,
+            radius: None,
+            orientation: None,
+            confidence: None,
+        }
+    }
+}
+
+/// Information about a touch surface, implemented by back-ends.
+pub trait TouchDevice {
+    /// Returns the width and height of the surface in pixels.
+    fn size(&self) -> [u32; 2];
+    /// Returns the width and height of the surface in millimeters,
+    /// if known.
+    fn physical_size(&self) -> Option<[f64; 2]>;
+    /// Returns the maximum number of simultaneous contacts, if known.
+    fn max_contacts(&self) -> Option<u32>;
+
+    /// Returns the number of pixels per millimeter, if known.
+    ///
+    /// Useful for resolution independent gesture thresholds.
+    fn pixels_per_mm(&self) -> Option<f64> {
+        self.physical_size().map(|[w, _]| self.size()[0] as f64 / w)
+    }
+}
+
+/// Vibration motor of a touch device, implemented by back-ends.
+///
+/// Short pulses give tactile feedback for touch interactions,
+/// e.g. when a long press is recognized.
+pub trait Vibrator {
+    /// Vibrates for `duration` seconds at a strength from 0 to 1.
+    ///
+    /// Back-ends without strength control vibrate at full strength.
+    /// Does nothing on devices without a vibration motor.
+    fn pulse(&mut self, duration: f64, strength: f64);
+    /// Returns `true` if the device has a vibration motor.
+    fn can_vibrate(&self) -> bool;
+}
+
+/// Enforces well-formed touch contact lifecycles.
+///
+/// Every contact begins with `Began`, followed by any number of `Moved` or
+/// `Stationary`, and ends with `Ended` or `Cancelled`. Touches of unknown
+/// contacts are dropped, and contacts that begin again without ending,
+/// or are active when focus is lost, are cancelled.
+/// Contacts cancelled on focus loss are sorted by id.
+#[derive(Clone, Debug)]
+pub struct TouchValidator {
+    active: HashMap<i64, Touch>,
+}
+
+impl TouchValidator {
+    /// Creates a new validator without active contacts.
+    pub fn new() -> TouchValidator {
+        TouchValidator { active: HashMap::new() }
+    }
+
+    /// Processes an input, returning the inputs to pass on.
+    pub fn handle_input(&mut self, input: &Input) -> Vec<Input> {
+        let touch = match *input {
+            Input::Touch(touch) => touch,
+            Input::Focus(false) => {
+                let mut touches: Vec<Touch> = self.active.drain()
+                    .map(|(_, touch)| touch).collect();
+                touches.sort_by_key(|touch| touch.id);
+                let mut out: Vec<Input> = touches.into_iter()
+                    .map(|touch| Input::Touch(Touch {
+                        phase: TouchPhase::Cancelled, ..touch
+                    }))
+                    .collect();
+                out.push(input.clone());
+                return out;
+            }
+            ref x => return vec![x.clone()],
+        };
+        let mut out = vec![];
+        match touch.phase {
+            TouchPhase::Began => {
+                if let Some(old) = self.active.insert(touch.id, touch) {
+                    out.push(Input::Touch(Touch {
+                        phase: TouchPhase::Cancelled, ..old
+                    }));
+                }
+            }
+            TouchPhase::Moved | TouchPhase::Stationary => {
+                match self.active.get_mut(&touch.id) {
+                    Some(active) => *active = touch,
+                    None => return out,
+                }
+            }
+            TouchPhase::Ended | TouchPhase::Cancelled => {
+                if self.active.remove(&touch.id).is_none() { return out; }
+            }
+        }
+        out.push(Input::Touch(touch));
+        out
+    }
+}
+
+impl Default for TouchValidator {
+    fn default() -> TouchValidator { TouchValidator::new() }
+}
+
+/// Suppresses touch contacts with low confidence, e.g. resting palms.
+///
+/// Contacts without a reported confidence are passed on.
+/// Once rejected, a contact stays rejected until it ends.
+#[derive(Clone, Debug)]
+pub struct PalmRejection {
+    /// Contacts with a confidence below the threshold are rejected.
+    pub threshold: f64,
+    passed: HashSet<i64>,
+    rejected: HashSet<i64>,
+}
+
+impl PalmRejection {
+    /// Creates a new palm rejection stage.
+    pub fn new(threshold: f64) -> PalmRejection {
+        PalmRejection {
+            threshold: threshold,
+            passed: HashSet::new(),
+            rejected: HashSet::new(),
+        }
+    }
+
+    /// Processes an input, returning the input to pass on.
+    ///
+    /// When a contact that was passed on is rejected,
+    /// it is cancelled at its current position.
+    pub fn handle_input(&mut self, input: &Input) -> Option<Input> {
+        let touch = match *input {
+            Input::Touch(touch) => touch,
+            ref x => return Some(x.clone()),
+        };
+        let ended = touch.phase.is_end();
+        if self.rejected.contains(&touch.id) {
+            if ended { self.rejected.remove(&touch.id); }
+            return None;
+        }
+        let low = touch.confidence.map(|c| c < self.threshold).unwrap_or(false);
+        if low && !ended {
+            self.rejected.insert(touch.id);
+            if !self.passed.remove(&touch.id) { return None; }
+            return Some(Input::Touch(Touch { phase: TouchPhase::Cancelled, ..touch }));
+        }
+        if ended {
+            self.passed.remove(&touch.id);
+        } else {
+            self.passed.insert(touch.id);
+        }
+        Some(Input::Touch(touch))
+    }
+}
+
+/// Predicts touch positions ahead of time from their recent velocity.
+///
+/// Drawing applications can render the predicted positions
+/// to reduce perceived latency. Several moves in one frame are
+/// measured together from the last position of an earlier frame.
+#[derive(Clone, Debug)]
+pub struct TouchPredictor {
+    /// Seconds to predict ahead.
+    pub lookahead: f64,
+    time: f64,
+    contacts: HashMap<i64, Contact>,
+}
+
+/// Samples of a contact used to compute its velocity.
+#[derive(Copy, Clone, Debug)]
+struct Contact {
+    // Last position and time of an earlier frame.
+    start: ([f64; 2], f64),
+    // Last position and time.
+    last: ([f64; 2], f64),
+    velocity: [f64; 2],
+}
+
+impl Contact {
+    fn new(position: [f64; 2], time: f64) -> Contact {
+        Contact {
+            start: (position, time),
+            last: (position, time),
+            velocity: [0.0, 0.0],
+        }
+    }
+}
+
+impl TouchPredictor {
+    /// Creates a new predictor predicting `lookahead` seconds ahead.
+    pub fn new(lookahead: f64) -> TouchPredictor {
+        TouchPredictor {
+            lookahead: lookahead,
+            time: 0.0,
+            contacts: HashMap::new(),
+        }
+    }
+
+    /// Advances time.
+    pub fn update(&mut self, dt: f64) {
+        self.time += dt;
+    }
+
+    /// Handles an input, returning the predicted contact for moved touches.
+    pub fn handle_input(&mut self, input: &Input) -> Option<Touch> {
+        let touch = match *input {
+            Input::Touch(touch) => touch,
+            _ => return None,
+        };
+        let time = self.time;
+        match touch.phase {
+            TouchPhase::Began => {
+                self.contacts.insert(touch.id, Contact::new(touch.position, time));
+                None
+            }
+            TouchPhase::Moved => {
+                let contact = self.contacts.entry(touch.id)
+                    .or_insert(Contact::new(touch.position, time));
+                if time > contact.last.1 {
+                    contact.start = contact.last;
+                }
+                contact.last = (touch.position, time);
+                let (start, t) = contact.start;
+                if time > t {
+                    contact.velocity = [(touch.position[0] - start[0]) / (time - t),
+                                        (touch.position[1] - start[1]) / (time - t)];
+                }
+                let velocity = contact.velocity;
+                Some(Touch {
+                    position: [touch.position[0] + velocity[0] * self.lookahead,
+                               touch.position[1] + velocity[1] * self.lookahead],
+                    ..touch
+                })
+            }
+            TouchPhase::Stationary => None,
+            TouchPhase::Ended | TouchPhase::Cancelled => {
+                self.contacts.remove(&touch.id);
+                None
+            }
+        }
+    }
+}
+
+/// Represent a stylus button.
+#[derive(Copy, Clone, RustcDecodable, RustcEncodable, PartialEq,
+    Eq, Ord, PartialOrd, Hash, Debug)]
+pub enum StylusButton {
+    /// The tip touching the surface.
+    Tip,
+    /// The eraser tip touching the surface.
+    Eraser,
+    /// The first barrel button.
+    Barrel1,
+    /// The second barrel button.
+    Barrel2,
+}
+
+/// A stylus hovering above a surface without touching it.
+#[derive(Copy, Clone, RustcDecodable, RustcEncodable, PartialEq, Debug)]
+pub enum PenHover {
+    /// The pen came within range of the surface.
+    InRange,
+    /// The pen moved, with x and y in window coordinates and the height
+    /// above the surface from 0 to 1, if reported by the back-end.
+    Move([f64; 2], Option<f64>),
+    /// The pen left the range of the surface.
+    OutOfRange,
+}
+
+#[cfg(feature = "quickcheck")]
+impl Arbitrary for TouchPhase {
+    fn arbitrary(g: &mut Gen) -> TouchPhase {
+        *g.choose(&[TouchPhase::Began, TouchPhase::Moved, TouchPhase::Stationary,
+                    TouchPhase::Ended, TouchPhase::Cancelled]).unwrap()
+    }
+}
+
+#[cfg(feature = "quickcheck")]
+impl Arbitrary for Touch {
+    fn arbitrary(g: &mut Gen) -> Touch {
+        Touch {
+            id: i64::arbitrary(g),
+            phase: TouchPhase::arbitrary(g),
+            position: [f64::arbitrary(g), f64::arbitrary(g)],
+            pressure: Option::arbitrary(g),
+            radius: Option::<(f64, f64)>::arbitrary(g).map(|(a, b)| [a, b]),
+            orientation: Option::arbitrary(g),
+            confidence: Option::arbitrary(g),
+        }
+    }
+}
+
+#[cfg(feature = "quickcheck")]
+impl Arbitrary for PenHover {
+    fn arbitrary(g: &mut Gen) -> PenHover {
+        match u8::arbitrary(g) % 3 {
+            0 => PenHover::InRange,
+            1 => PenHover::Move([f64::arbitrary(g), f64::arbitrary(g)],
+                                Option::arbitrary(g)),
+            _ => PenHover::OutOfRange,
+        }
+    }
+}
+
+#[cfg(feature = "quickcheck")]
+impl Arbitrary for StylusButton {
+    fn arbitrary(g: &mut Gen) -> StylusButton {
+        *g.choose(&[StylusButton::Tip, StylusButton::Eraser,
+                    StylusButton::Barrel1, StylusButton::Barrel2]).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Input;
+
+    #[test]
+    fn test_palm_rejection() {
+        let mut palm = PalmRejection::new(0.5);
+        let mut touch = Touch::new(1, TouchPhase::Began, [0.0, 0.0]);
+        touch.confidence = Some(0.9);
+        assert_eq!(palm.handle_input(&Input::Touch(touch)), Some(Input::Touch(touch)));
+        touch.phase = TouchPhase::Moved;
+        touch.confidence = Some(0.1);
+        let cancelled = Touch { phase: TouchPhase::Cancelled, ..touch };
+        assert_eq!(palm.handle_input(&Input::Touch(touch)), Some(Input::Touch(cancelled)));
+        touch.confidence = Some(0.9);
+        assert_eq!(palm.handle_input(&Input::Touch(touch)), None);
+        touch.phase = TouchPhase::Ended;
+        assert_eq!(palm.handle_input(&Input::Touch(touch)), None);
+    }
+
+    #[test]
+    fn test_touch_validator() {
+        let mut validator = TouchValidator::new();
+        let began = Touch::new(1, TouchPhase::Began, [0.0, 0.0]);
+        let moved = Touch::new(1, TouchPhase::Moved, [1.0, 0.0]);
+        assert!(validator.handle_input(&Input::Touch(moved)).is_empty());
+        assert_eq!(validator.handle_input(&Input::Touch(began)),
+                   vec![Input::Touch(began)]);
+        assert_eq!(validator.handle_input(&Input::Touch(moved)),
+                   vec![Input::Touch(moved)]);
+        let cancelled = Touch { phase: TouchPhase::Cancelled, ..moved };
+        assert_eq!(validator.handle_input(&Input::Touch(began)),
+                   vec![Input::Touch(cancelled), Input::Touch(began)]);
+    }
+
+    #[test]
+    fn test_touch_predictor() {
+        let touch = |phase, x| Input::Touch(Touch::new(1, phase, [x, 0.0]));
+        let mut predictor = TouchPredictor::new(0.5);
+        assert_eq!(predictor.handle_input(&touch(TouchPhase::Began, 0.0)), None);
+        predictor.update(1.0);
+        assert_eq!(predictor.handle_input(&touch(TouchPhase::Moved, 2.0)).unwrap().position,
+                   [3.0, 0.0]);
+        predictor.update(1.0);
+        // Several moves in one frame use the latest position.
+        assert_eq!(predictor.handle_input(&touch(TouchPhase::Moved, 3.0)).unwrap().position,
+                   [3.5, 0.0]);
+        assert_eq!(predictor.handle_input(&touch(TouchPhase::Moved, 6.0)).unwrap().position,
+                   [8.0, 0.0]);
+        predictor.update(1.0);
+        assert_eq!(predictor.handle_input(&touch(TouchPhase::Moved, 7.0)).unwrap().position,
+                   [7.5, 0.0]);
+        assert_eq!(predictor.handle_input(&touch(TouchPhase::Ended, 7.0)), None);
+    }
+
+    #[test]
+    fn test_touch_validator_focus_loss() {
+        let mut validator = TouchValidator::new();
+        for &id in &[5, -2, 9, 0, 3] {
+            validator.handle_input(&Input::Touch(Touch::new(id, TouchPhase::Began, [0.0, 0.0])));
+        }
+        let out = validator.handle_input(&Input::Focus(false));
+        let cancelled: Vec<i64> = out.iter().filter_map(|input| match *input {
+            Input::Touch(touch) if touch.phase == TouchPhase::Cancelled => Some(touch.id),
+            _ => None,
+        }).collect();
+        assert_eq!(cancelled, vec![-2, 0, 3, 5, 9]);
+        assert_eq!(out.last(), Some(&Input::Focus(false)));
+        assert_eq!(validator.handle_input(&Input::Focus(false)), vec![Input::Focus(false)]);
+    }
+}
@@ -0,0 +1,133 @@
+//! Turbo (auto-fire) for held buttons.
+
+use std::collections::HashMap;
+
+use { Button, Input };
+
+/// Synthesizes repeated presses while turbo buttons are held.
+///
+/// Turbo gamepad buttons are pad-independent,
+/// and fire on each gamepad they are held on. Synthesized inputs of
+/// several held buttons are in the order the buttons were pressed.
+#[derive(Clone, Debug)]
+pub struct Turbo {
+    rates: HashMap<Button, f64>,
+    // Held buttons in the order they were pressed, time since last toggle
+    // and whether the synthesized button is down.
+    held: Vec<(Button, f64, bool)>,
+}
+
+impl Turbo {
+    /// Creates a new turbo stage without any turbo buttons.
+    pub fn new() -> Turbo {
+        Turbo {
+            rates: HashMap::new(),
+            held: vec![],
+        }
+    }
+
+    /// Enables turbo for a button with the rate in presses per second.
+    pub fn set_rate(&mut self, button: Button, rate: f64) {
+        self.rates.insert(button.any_gamepad(), rate);
+    }
+
+    /// Disables turbo for a button.
+    pub fn remove(&mut self, button: &Button) {
+        let button = button.any_gamepad();
+        self.rates.remove(&button);
+        self.held.retain(|&(held, _, _)| held.any_gamepad() != button);
+    }
+
+    /// Returns the turbo rate of a button in presses per second.
+    pub fn rate(&self, button: &Button) -> Option<f64> {
+        self.rates.get(&button.any_gamepad()).cloned()
+    }
+
+    /// Processes an input, returning the inputs to pass on.
+    pub fn handle_input(&mut self, input: &Input) -> Vec<Input> {
+        match *input {
+            Input::Press(button) if self.rate(&button).is_some() => {
+                if self.held.iter().any(|&(held, _, _)| held == button) {
+                    return vec![];
+                }
+                self.held.push((button, 0.0, true));
+                vec![Input::Press(button)]
+            }
+            Input::Release(button) if self.rate(&button).is_some() => {
+                match self.held.iter().position(|&(held, _, _)| held == button) {
+                    Some(i) if self.held.remove(i).2 => vec![Input::Release(button)],
+                    _ => vec![],
+                }
+            }
+            ref x => vec![x.clone()],
+        }
+    }
+
+    /// Advances time, returning the synthesized presses and releases.
+    pub fn update(&mut self, dt: f64) -> Vec<Input> {
+        let mut out = vec![];
+        for &mut (ref button, ref mut time, ref mut down) in &mut self.held {
+            let rate = self.rates[&button.any_gamepad()];
+            if rate <= 0.0 { continue; }
+            let half_period = 0.5 / rate;
+            *time += dt;
+            while *time >= half_period {
+                *time -= half_period;
+                *down = !*down;
+                out.push(if *down {
+                    Input::Press(*button)
+                } else {
+                    Input::Release(*button)
+                });
+            }
+        }
+        out
+    }
+}
+
+impl Default for Turbo {
+    fn default() -> Turbo { Turbo::new() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use { Button, GamepadButton, Input, Key, MouseButton };
+
+    #[test]
+    fn test_turbo() {
+        let left = Button::Mouse(MouseButton::Left);
+        let mut turbo = Turbo::new();
+        turbo.set_rate(left, 2.0);
+        assert_eq!(turbo.handle_input(&Input::Press(left)), vec![Input::Press(left)]);
+        assert_eq!(turbo.update(0.5), vec![Input::Release(left), Input::Press(left)]);
+        assert_eq!(turbo.update(0.25), vec![Input::Release(left)]);
+        assert!(turbo.handle_input(&Input::Release(left)).is_empty());
+        assert!(turbo.update(0.5).is_empty());
+    }
+
+    #[test]
+    fn test_order() {
+        let buttons: Vec<Button> = [Key::D, Key::A, Key::C, Key::B, Key::E].iter()
+            .map(|&key| Button::Keyboard(key)).collect();
+        let mut turbo = Turbo::new();
+        for &button in &buttons {
+            turbo.set_rate(button, 2.0);
+            turbo.handle_input(&Input::Press(button));
+        }
+        let released: Vec<Input> = buttons.iter().map(|&button| Input::Release(button)).collect();
+        assert_eq!(turbo.update(0.25), released);
+    }
+
+    #[test]
+    fn test_gamepads() {
+        let south = |id| Button::Gamepad(id, GamepadButton::South);
+        let mut turbo = Turbo::new();
+        turbo.set_rate(south(0), 2.0);
+        assert_eq!(turbo.rate(&south(2)), Some(2.0));
+        assert_eq!(turbo.handle_input(&Input::Press(south(2))), vec![Input::Press(south(2))]);
+        assert_eq!(turbo.update(0.25), vec![Input::Release(south(2))]);
+        turbo.remove(&south(1));
+        assert!(turbo.update(0.25).is_empty());
+    }
+}
@@ -0,0 +1,65 @@
+//! Steering wheels and pedals.
+
+/// Rotation range of a steering wheel, implemented by back-ends.
+pub trait Wheel {
+    /// Sets the lock-to-lock rotation range in degrees.
+    ///
+    /// Returns `false` if the wheel does not support the range.
+    fn set_rotation_range(&mut self, degrees: f64) -> bool;
+    /// Returns the lock-to-lock rotation range in degrees.
+    fn rotation_range(&self) -> f64;
+}
+
+/// Normalizes a steering angle in degrees from the center to -1..1
+/// for a lock-to-lock rotation range, e.g. of the simulated vehicle.
+pub fn normalize_steering(degrees: f64, range: f64) -> f64 {
+    if range <= 0.0 { return 0.0; }
+    (degrees / (range * 0.5)).max(-1.0).min(1.0)
+}
+
+/// How a wheel reports its throttle and brake pedals.
+#[derive(Copy, Clone, RustcDecodable, RustcEncodable, PartialEq, Eq, Hash, Debug)]
+pub enum PedalMode {
+    /// Separate throttle and brake axes from 0 to 1.
+    Split,
+    /// One axis from -1 (full brake) to 1 (full throttle),
+    /// as reported by some older wheels.
+    Combined,
+}
+
+impl PedalMode {
+    /// Returns separate throttle and brake values from 0 to 1.
+    ///
+    /// In split mode, `axes` are the throttle and brake axes.
+    /// In combined mode, the first axis is the combined axis
+    /// and the second is ignored.
+    pub fn separate(&self, axes: [f64; 2]) -> [f64; 2] {
+        let clamp = |x: f64| x.max(0.0).min(1.0);
+        match *self {
+            PedalMode::Split => [clamp(axes[0]), clamp(axes[1])],
+            PedalMode::Combined => [clamp(axes[0]), clamp(-axes[0])],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_steering() {
+        assert_eq!(normalize_steering(0.0, 900.0), 0.0);
+        assert_eq!(normalize_steering(225.0, 900.0), 0.5);
+        assert_eq!(normalize_steering(-450.0, 900.0), -1.0);
+        assert_eq!(normalize_steering(600.0, 900.0), 1.0);
+        assert_eq!(normalize_steering(90.0, 0.0), 0.0);
+    }
+
+    #[test]
+    fn test_pedals() {
+        assert_eq!(PedalMode::Split.separate([0.5, 0.25]), [0.5, 0.25]);
+        assert_eq!(PedalMode::Split.separate([1.5, -0.5]), [1.0, 0.0]);
+        assert_eq!(PedalMode::Combined.separate([0.5, 0.9]), [0.5, 0.0]);
+        assert_eq!(PedalMode::Combined.separate([-0.75, 0.0]), [0.0, 0.75]);
+    }
+}
@@ -0,0 +1,511 @@
+//! Compact binary encoding of input for sending over networks.
+//!
+//! A stream starts with a header of the magic bytes `PIN` and the
+//! format version. Each input follows as the time since the previous
+//! input in microseconds, a tag and the payload. Integers are encoded
+//! as variable length integers, so small gamepad ids and sizes take
+//! a single byte.
+//!
+//! Device ids are not interned in a table. The only device ids in
+//! inputs are gamepad ids, which are small numbers assigned by the
+//! back-end, so their varints already take a single byte.
+
+use std::error::Error;
+use std::fmt;
+
+use num::{ FromPrimitive, ToPrimitive };
+
+use { Battery, Button, DrumPad, GamepadAxis, GamepadButton, Input, Key, Motion, MouseButton,
+    PenHover, StylusButton, Touch };
+use gamepad;
+use touch::TouchPhase;
+
+/// The version of the format written by the encoder.
+pub const VERSION: u8 = 1;
+
+const MAGIC: &'static [u8] = b"PIN";
+
+// Index of gamepad buttons and axes unknown to this crate,
+// followed by the back-end's code.
+const OTHER: u8 = 255;
+
+static STYLUS_BUTTONS: &'static [StylusButton] = &[
+    StylusButton::Tip, StylusButton::Eraser,
+    StylusButton::Barrel1, StylusButton::Barrel2,
+];
+
+static PHASES: &'static [TouchPhase] = &[
+    TouchPhase::Began, TouchPhase::Moved, TouchPhase::Stationary,
+    TouchPhase::Ended, TouchPhase::Cancelled,
+];
+
+static PADS: &'static [DrumPad] = &[
+    DrumPad::Red, DrumPad::Yellow, DrumPad::Blue, DrumPad::Green, DrumPad::Kick,
+];
+
+/// An error decoding input.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum WireError {
+    /// The data does not start with the magic bytes.
+    BadMagic,
+    /// The data has a newer format version.
+    UnknownVersion(u8),
+    /// The data ended within an input.
+    Truncated,
+    /// The data contains an invalid value.
+    Invalid,
+}
+
+impl fmt::Display for WireError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            WireError::BadMagic => write!(f, "not an input stream"),
+            WireError::UnknownVersion(v) => write!(f, "unknown format version {}", v),
+            WireError::Truncated => write!(f, "truncated input"),
+            WireError::Invalid => write!(f, "invalid input"),
+        }
+    }
+}
+
+impl Error for WireError {}
+
+/// Writes the header of a stream.
+pub fn write_header(buf: &mut Vec<u8>) {
+    buf.extend(MAGIC);
+    buf.push(VERSION);
+}
+
+/// Reads the header of a stream.
+///
+/// Returns the format version and the length of the header.
+pub fn read_header(bytes: &[u8]) -> Result<(u8, usize), WireError> {
+    if bytes.len() < MAGIC.len() + 1 { return Err(WireError::Truncated); }
+    if &bytes[..MAGIC.len()] != MAGIC { return Err(WireError::BadMagic); }
+    let version = bytes[MAGIC.len()];
+    if version == 0 || version > VERSION { return Err(WireError::UnknownVersion(version)); }
+    Ok((version, MAGIC.len() + 1))
+}
+
+/// Encodes inputs with their time in seconds.
+#[derive(Copy, Clone, Debug)]
+pub struct Encoder {
+    // Time of the previous input in microseconds.
+    last: u64,
+}
+
+impl Encoder {
+    /// Creates a new encoder starting at time 0.
+    pub fn new() -> Encoder {
+        Encoder { last: 0 }
+    }
+
+    /// Appends an input to a buffer.
+    ///
+    /// Times before the previous input are encoded as the previous time.
+    pub fn encode(&mut self, time: f64, input: &Input, buf: &mut Vec<u8>) {
+        let micros = (time.max(0.0) * 1e6).round() as u64;
+        let micros = micros.max(self.last);
+        write_varint(buf, micros - self.last);
+        self.last = micros;
+        match *input {
+            Input::Press(button) => { buf.push(0); write_button(buf, button); }
+            Input::Release(button) => { buf.push(1); write_button(buf, button); }
+            Input::Move(motion) => { buf.push(2); write_motion(buf, motion); }
+            Input::Text(ref text) => {
+                buf.push(3);
+                write_varint(buf, text.len() as u64);
+                buf.extend(text.as_bytes());
+            }
+            Input::Resize(w, h) => {
+                buf.push(4);
+                write_varint(buf, w as u64);
+                write_varint(buf, h as u64);
+            }
+            Input::Focus(focus) => { buf.push(5); buf.push(focus as u8); }
+            Input::OnScreenKeyboard(shown) => { buf.push(6); buf.push(shown as u8); }
+            Input::Touch(touch) => { buf.push(7); write_touch(buf, touch); }
+            Input::PenHover(hover) => {
+                buf.push(8);
+                match hover {
+                    PenHover::InRange => buf.push(0),
+                    PenHover::Move(pos, height) => {
+                        buf.push(1);
+                        write_f64(buf, pos[0]);
+                        write_f64(buf, pos[1]);
+                        write_opt_f64(buf, height);
+                    }
+                    PenHover::OutOfRange => buf.push(2),
+                }
+            }
+            Input::GamepadTouch(id, touch) => {
+                buf.push(9);
+                write_varint(buf, id as u64);
+                write_touch(buf, touch);
+            }
+            Input::GamepadBattery(id, battery) => {
+                buf.push(10);
+                write_varint(buf, id as u64);
+                // 255 is an unknown charge.
+                buf.push(battery.percent.unwrap_or(255));
+                buf.push(battery.charging as u8);
+            }
+            Input::GamepadConnected(id, connected) => {
+                buf.push(11);
+                write_varint(buf, id as u64);
+                buf.push(connected as u8);
+            }
+            Input::DrumHit(id, pad, velocity) => {
+                buf.push(12);
+                write_varint(buf, id as u64);
+                buf.push(index(PADS, &pad));
+                write_f64(buf, velocity);
+            }
+        }
+    }
+}
+
+impl Default for Encoder {
+    fn default() -> Encoder { Encoder::new() }
+}
+
+/// Decodes inputs with their time in seconds.
+#[derive(Copy, Clone, Debug)]
+pub struct Decoder {
+    // Time of the previous input in microseconds.
+    last: u64,
+}
+
+impl Decoder {
+    /// Creates a new decoder starting at time 0.
+    pub fn new() -> Decoder {
+        Decoder { last: 0 }
+    }
+
+    /// Decodes the input at the start of `bytes`.
+    ///
+    /// Returns the time, the input and the number of bytes read.
+    pub fn decode(&mut self, bytes: &[u8]) -> Result<(f64, Input, usize), WireError> {
+        let mut r = Reader { bytes: bytes, pos: 0 };
+        let delta = r.varint()?;
+        let input = match r.byte()? {
+            0 => Input::Press(r.button()?),
+            1 => Input::Release(r.button()?),
+            2 => Input::Move(r.motion()?),
+            3 => {
+                let len = r.varint()? as usize;
+                let text = r.take(len)?;
+                Input::Text(String::from_utf8(text.to_vec()).map_err(|_| WireError::Invalid)?)
+            }
+            4 => Input::Resize(r.u32()?, r.u32()?),
+            5 => Input::Focus(r.bool()?),
+            6 => Input::OnScreenKeyboard(r.bool()?),
+            7 => Input::Touch(r.touch()?),
+            8 => Input::PenHover(match r.byte()? {
+                0 => PenHover::InRange,
+                1 => PenHover::Move([r.f64()?, r.f64()?], r.opt_f64()?),
+                2 => PenHover::OutOfRange,
+                _ => return Err(WireError::Invalid),
+            }),
+            9 => Input::GamepadTouch(r.u32()?, r.touch()?),
+            10 => {
+                let id = r.u32()?;
+                let percent = match r.byte()? { 255 => None, x => Some(x) };
+                Input::GamepadBattery(id, Battery { percent: percent, charging: r.bool()? })
+            }
+            11 => Input::GamepadConnected(r.u32()?, r.bool()?),
+            12 => Input::DrumHit(r.u32()?, r.item(PADS)?, r.f64()?),
+            _ => return Err(WireError::Invalid),
+        };
+        self.last = self.last.checked_add(delta).ok_or(WireError::Invalid)?;
+        Ok((self.last as f64 * 1e-6, input, r.pos))
+    }
+}
+
+impl Default for Decoder {
+    fn default() -> Decoder { Decoder::new() }
+}
+
+fn index<T: PartialEq>(items: &[T], item: &T) -> u8 {
+    items.iter().position(|x| x == item).unwrap() as u8
+}
+
+pub(crate) fn write_varint(buf: &mut Vec<u8>, mut x: u64) {
+    while x >= 0x80 {
+        buf.push(x as u8 | 0x80);
+        x >>= 7;
+    }
+    buf.push(x as u8);
+}
+
+// Reads a variable length integer, advancing `pos`.
+pub(crate) fn read_varint(bytes: &[u8], pos: &mut usize) -> Result<u64, WireError> {
+    let mut r = Reader { bytes: bytes, pos: *pos };
+    let x = r.varint()?;
+    *pos = r.pos;
+    Ok(x)
+}
+
+fn write_f64(buf: &mut Vec<u8>, x: f64) {
+    let bits = x.to_bits();
+    for i in 0..8 {
+        buf.push((bits >> (i * 8)) as u8);
+    }
+}
+
+fn write_opt_f64(buf: &mut Vec<u8>, x: Option<f64>) {
+    match x {
+        Some(x) => { buf.push(1); write_f64(buf, x); }
+        None => buf.push(0),
+    }
+}
+
+fn write_button(buf: &mut Vec<u8>, button: Button) {
+    match button {
+        Button::Keyboard(key) => {
+            buf.push(0);
+            write_varint(buf, key.to_u64().unwrap());
+        }
+        Button::Mouse(button) => {
+            buf.push(1);
+            write_varint(buf, button.to_u64().unwrap());
+        }
+        Button::Stylus(button) => {
+            buf.push(2);
+            buf.push(index(STYLUS_BUTTONS, &button));
+        }
+        Button::Gamepad(id, button) => {
+            buf.push(3);
+            write_varint(buf, id as u64);
+            match button {
+                GamepadButton::Other(code) => {
+                    buf.push(OTHER);
+                    write_varint(buf, code as u64);
+                }
+                button => buf.push(index(gamepad::BUTTONS, &button)),
+            }
+        }
+    }
+}
+
+fn write_motion(buf: &mut Vec<u8>, motion: Motion) {
+    match motion {
+        Motion::MouseCursor(x, y) => { buf.push(0); write_f64(buf, x); write_f64(buf, y); }
+        Motion::MouseRelative(x, y) => { buf.push(1); write_f64(buf, x); write_f64(buf, y); }
+        Motion::MouseScroll(x, y) => { buf.push(2); write_f64(buf, x); write_f64(buf, y); }
+        Motion::GamepadAxis(id, axis, value) => {
+            buf.push(3);
+            write_varint(buf, id as u64);
+            match axis {
+                GamepadAxis::Other(code) => {
+                    buf.push(OTHER);
+                    write_varint(buf, code as u64);
+                }
+                axis => buf.push(index(gamepad::AXES, &axis)),
+            }
+            write_f64(buf, value);
+        }
+        Motion::GamepadGyro(id, v) => {
+            buf.push(4);
+            write_varint(buf, id as u64);
+            for &x in &v { write_f64(buf, x); }
+        }
+        Motion::GamepadAccel(id, v) => {
+            buf.push(5);
+            write_varint(buf, id as u64);
+            for &x in &v { write_f64(buf, x); }
+        }
+    }
+}
+
+fn write_touch(buf: &mut Vec<u8>, touch: Touch) {
+    // Zigzag encoding keeps small negative ids short.
+    write_varint(buf, ((touch.id << 1) ^ (touch.id >> 63)) as u64);
+    buf.push(index(PHASES, &touch.phase));
+    write_f64(buf, touch.position[0]);
+    write_f64(buf, touch.position[1]);
+    write_opt_f64(buf, touch.pressure);
+    match touch.radius {
+        Some(r) => { buf.push(1); write_f64(buf, r[0]); write_f64(buf, r[1]); }
+        None => buf.push(0),
+    }
+    write_opt_f64(buf, touch.orientation);
+    write_opt_f64(buf, touch.confidence);
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], WireError> {
+        if self.bytes.len() - self.pos < n { return Err(WireError::Truncated); }
+        let bytes = &self.bytes[self.pos..self.pos + n];
+        self.pos += n;
+        Ok(bytes)
+    }
+
+    fn byte(&mut self) -> Result<u8, WireError> {
+        self.take(1).map(|b| b[0])
+    }
+
+    fn bool(&mut self) -> Result<bool, WireError> {
+        match self.byte()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(WireError::Invalid),
+        }
+    }
+
+    fn varint(&mut self) -> Result<u64, WireError> {
+        let mut x = 0;
+        for i in 0..10 {
+            let b = self.byte()?;
+            x |= ((b & 0x7f) as u64) << (i * 7);
+            if b & 0x80 == 0 { return Ok(x); }
+        }
+        Err(WireError::Invalid)
+    }
+
+    fn u32(&mut self) -> Result<u32, WireError> {
+        let x = self.varint()?;
+        if x > u32::max_value() as u64 { return Err(WireError::Invalid); }
+        Ok(x as u32)
+    }
+
+    fn f64(&mut self) -> Result<f64, WireError> {
+        let bytes = self.take(8)?;
+        let mut bits = 0u64;
+        for i in 0..8 {
+            bits |= (bytes[i] as u64) << (i * 8);
+        }
+        Ok(f64::from_bits(bits))
+    }
+
+    fn opt_f64(&mut self) -> Result<Option<f64>, WireError> {
+        if self.bool()? { self.f64().map(Some) } else { Ok(None) }
+    }
+
+    fn peek(&self) -> Result<u8, WireError> {
+        self.bytes.get(self.pos).cloned().ok_or(WireError::Truncated)
+    }
+
+    fn item<T: Copy>(&mut self, items: &[T]) -> Result<T, WireError> {
+        items.get(self.byte()? as usize).cloned().ok_or(WireError::Invalid)
+    }
+
+    fn button(&mut self) -> Result<Button, WireError> {
+        Ok(match self.byte()? {
+            0 => Button::Keyboard(Key::from_u64(self.varint()?).ok_or(WireError::Invalid)?),
+            1 => Button::Mouse(MouseButton::from_u64(self.varint()?)
+                .ok_or(WireError::Invalid)?),
+            2 => Button::Stylus(self.item(STYLUS_BUTTONS)?),
+            3 => Button::Gamepad(self.u32()?, match self.peek()? {
+                OTHER => { self.byte()?; GamepadButton::Other(self.u32()?) }
+                _ => self.item(gamepad::BUTTONS)?,
+            }),
+            _ => return Err(WireError::Invalid),
+        })
+    }
+
+    fn motion(&mut self) -> Result<Motion, WireError> {
+        Ok(match self.byte()? {
+            0 => Motion::MouseCursor(self.f64()?, self.f64()?),
+            1 => Motion::MouseRelative(self.f64()?, self.f64()?),
+            2 => Motion::MouseScroll(self.f64()?, self.f64()?),
+            3 => Motion::GamepadAxis(self.u32()?, match self.peek()? {
+                OTHER => { self.byte()?; GamepadAxis::Other(self.u32()?) }
+                _ => self.item(gamepad::AXES)?,
+            }, self.f64()?),
+            4 => Motion::GamepadGyro(self.u32()?, [self.f64()?, self.f64()?, self.f64()?]),
+            5 => Motion::GamepadAccel(self.u32()?, [self.f64()?, self.f64()?, self.f64()?]),
+            _ => return Err(WireError::Invalid),
+        })
+    }
+
+    fn touch(&mut self) -> Result<Touch, WireError> {
+        let zigzag = self.varint()?;
+        let id = (zigzag >> 1) as i64 ^ -((zigzag & 1) as i64);
+        let phase = self.item(PHASES)?;
+        let position = [self.f64()?, self.f64()?];
+        let pressure = self.opt_f64()?;
+        let radius = if self.bool()? { Some([self.f64()?, self.f64()?]) } else { None };
+        Ok(Touch {
+            id: id,
+            phase: phase,
+            position: position,
+            pressure: pressure,
+            radius: radius,
+            orientation: self.opt_f64()?,
+            confidence: self.opt_f64()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use { Button, GamepadAxis, GamepadButton, Input, Key, Motion };
+    use touch::{ Touch, TouchPhase };
+
+    #[test]
+    fn test_round_trip() {
+        let inputs = vec![
+            (0.5, Input::Press(Button::Keyboard(Key::A))),
+            (0.75, Input::Move(Motion::GamepadAxis(2, GamepadAxis::LeftStickY, -0.5))),
+            (1.0, Input::Text("hé".to_string())),
+            (1.0, Input::Touch(Touch::new(-3, TouchPhase::Moved, [1.0, 2.0]))),
+            (1.0, Input::Press(Button::Gamepad(1, GamepadButton::Other(300)))),
+            (1.0, Input::Move(Motion::GamepadAxis(1, GamepadAxis::Other(7), 0.25))),
+        ];
+        let mut buf = vec![];
+        write_header(&mut buf);
+        let mut encoder = Encoder::new();
+        for &(time, ref input) in &inputs {
+            encoder.encode(time, input, &mut buf);
+        }
+
+        let (version, mut pos) = read_header(&buf).unwrap();
+        assert_eq!(version, VERSION);
+        let mut decoder = Decoder::new();
+        for &(time, ref input) in &inputs {
+            let (t, decoded, n) = decoder.decode(&buf[pos..]).unwrap();
+            assert_eq!((t, &decoded), (time, input));
+            pos += n;
+        }
+        assert_eq!(pos, buf.len());
+        assert_eq!(decoder.decode(&buf[4..6]).unwrap_err(), WireError::Truncated);
+    }
+
+    #[cfg(feature = "quickcheck")]
+    #[test]
+    fn test_gamepad_round_trip() {
+        fn round_trip(id: u32, button: GamepadButton, axis: GamepadAxis) -> bool {
+            let inputs = [Input::Press(Button::Gamepad(id, button)),
+                          Input::Move(Motion::GamepadAxis(id, axis, 0.5))];
+            let mut buf = vec![];
+            let mut encoder = Encoder::new();
+            for input in &inputs {
+                encoder.encode(0.0, input, &mut buf);
+            }
+            let mut decoder = Decoder::new();
+            let mut pos = 0;
+            inputs.iter().all(|input| match decoder.decode(&buf[pos..]) {
+                Ok((_, decoded, n)) => { pos += n; decoded == *input }
+                Err(_) => false,
+            })
+        }
+        ::quickcheck::quickcheck(round_trip as fn(u32, GamepadButton, GamepadAxis) -> bool);
+    }
+
+    #[test]
+    fn test_time_overflow() {
+        let mut buf = vec![];
+        write_varint(&mut buf, u64::max_value());
+        buf.push(5);
+        buf.push(1);
+        let mut decoder = Decoder::new();
+        assert!(decoder.decode(&buf).is_ok());
+        assert_eq!(decoder.decode(&buf).unwrap_err(), WireError::Invalid);
+    }
+}